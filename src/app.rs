@@ -1,252 +1,248 @@
 mod card;
+mod card_tracker;
+mod controller;
 mod display;
+mod engine;
+mod evaluation;
+mod event;
 mod field;
 mod player;
+mod rules;
+mod save;
+mod score_breakdown;
 mod season;
+mod simulation;
+mod state;
+mod tactics;
 mod turn;
 mod win_condition;
 
-use card::{all_cards, Card};
-use field::{Field, Spot};
-use player::Player;
+use std::path::Path;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use card::{
+    all_cards,
+    card_list::{load_card_list, CardListError, CARD_LIST_PATH},
+    Card,
+};
+use controller::{PlayerController, Seat};
+use display::ReplayCommand;
+use engine::{GameEnd, GameEngine};
+use evaluation::{load_weights, Evaluator, WeightsError, WEIGHTS_PATH};
+use event::GameLog;
+use rules::{load_rule_set, RuleSet, RuleSetError, RULES_PATH};
+use save::{load_game, load_replay, save_game, save_replay, REPLAY_EXTENSION, SAVE_PATH};
 use season::Season;
-use turn::Turn;
-use win_condition::{check_two_ancients_house_rule, check_win, WinCondition};
+use simulation::Simulation;
 
-use rand::prelude::*;
-
-#[derive(PartialEq, Debug)]
-struct WinState {
-    pub player_index: usize,
-    pub game_won: bool,
-    pub condition: WinCondition,
-}
-
-pub struct App {
-    players: Vec<Player>,
-}
+pub struct App {}
 impl App {
     /// Create the game object
     pub fn new() -> Self {
-        Self { players: vec![] }
+        Self {}
     }
     /// Use this method to run the game
     pub fn run(&mut self) {
-        let num_players = display::get_num_players();
-
-        // TODO: support 2 or 3 players in a game
-        assert_eq!(4, num_players, "Only 4 players are supported right now...");
-        self.players = vec![
-            Player::new(Season::Spring),
-            Player::new(Season::Summer),
-            Player::new(Season::Autumn),
-            Player::new(Season::Winter),
-        ];
-
-        // Shuffles and distributes the decks to the players
-        self.prepare_decks();
-
-        // Once the game ends, use the win state to display a message
-        let win_state = self.game_loop();
-        display::game_over(
-            self.players[win_state.player_index].season(),
-            win_state.condition,
-        );
-    }
-    fn num_players(&self) -> usize {
-        self.players.len()
+        let cards = match Self::load_cards() {
+            Ok(cards) => cards,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        let evaluator = match Self::load_evaluator() {
+            Ok(evaluator) => evaluator,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        let save_path = Path::new(SAVE_PATH);
+        // Only the save a game was resumed from belongs to it
+        let (mut engine, resumed) = match Self::resume_saved_game(save_path, evaluator) {
+            Some(engine) => (engine, true),
+            None => {
+                let rules = match Self::load_rules() {
+                    Ok(rules) => rules,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return;
+                    }
+                };
+                let num_players = display::get_num_players();
+                let seats = display::get_seats(&Season::at_table(num_players));
+                let seed = display::get_seed().unwrap_or_else(rand::random);
+                display::show_seed(seed);
+
+                // Shuffles and distributes the decks to the players
+                let engine = GameEngine::new(
+                    Self::controllers(seats, seed, evaluator),
+                    rules,
+                    cards,
+                    seed,
+                );
+                (engine, false)
+            }
+        };
+        engine.set_undo_policy(display::get_undo_policy());
+
+        match engine.run() {
+            // Once the game ends, use the win state to display a message
+            GameEnd::Won(win_state) => {
+                display::game_over(
+                    engine.state().players()[win_state.player_index].season(),
+                    &win_state.condition,
+                );
+                if resumed {
+                    if let Err(e) = std::fs::remove_file(save_path) {
+                        eprintln!("Could not remove the finished game's save: {}", e);
+                    }
+                }
+                Self::offer_replay(engine.log());
+            }
+            GameEnd::SaveAndQuit => match save_game(save_path, &engine.save()) {
+                Ok(()) => display::game_saved(SAVE_PATH),
+                Err(e) => eprintln!("{}", e),
+            },
+        }
     }
-    /// The main game loop that runs over multiple rounds
-    fn game_loop(&mut self) -> WinState {
-        let mut first_player = 0usize;
+    /// Step back and forth through the game saved in a replay file
+    pub fn view_replay(&mut self, path: &Path) {
+        let log = match load_replay(path) {
+            Ok(log) => log,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        let steps = log
+            .replay_steps()
+            .expect("Replays are checked when they are loaded");
+        let mut step_index = 0;
         loop {
-            let win_state = self.play_round(first_player);
-            if win_state.game_won {
-                return win_state;
+            display::show_replay_step(step_index, steps.len(), &steps[step_index]);
+            let has_next = step_index + 1 < steps.len();
+            match display::get_replay_command(step_index > 0, has_next) {
+                ReplayCommand::Next => step_index += 1,
+                ReplayCommand::Previous => step_index -= 1,
+                ReplayCommand::Quit => return,
             }
-            first_player = win_state.player_index;
-            self.complete_round(win_state);
         }
     }
-    /// Load all 120 cards, shuffle them together, and distribute decks to the players
-    fn prepare_decks(&mut self) {
-        let mut all_cards = all_cards();
-        assert_eq!(120, all_cards.len());
-        all_cards.shuffle(&mut rand::thread_rng());
-
-        let player1_deck = all_cards.split_off(90);
-        let player2_deck = all_cards.split_off(60);
-        let player3_deck = all_cards.split_off(30);
-        let player4_deck = all_cards;
-
-        let decks = [player1_deck, player2_deck, player3_deck, player4_deck];
-        self.players
-            .iter_mut()
-            .zip(decks.into_iter())
-            .for_each(|(p, d)| p.set_deck(d));
+    /// Play computer players against each other without a terminal, as the
+    /// command line options say, and report how they did
+    pub fn simulate(&mut self, args: &[String]) {
+        let simulation = match Simulation::from_args(args) {
+            Ok(simulation) => simulation,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        let loaded = Self::load_cards()
+            .map_err(|e| e.to_string())
+            .and_then(|cards| Ok((cards, Self::load_evaluator().map_err(|e| e.to_string())?)));
+        let (cards, evaluator) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        // Preset rules named on the command line come first, then the rules file
+        let rules_path = Path::new(RULES_PATH);
+        let rules = match simulation.rules {
+            Some(rules) => rules,
+            None if rules_path.exists() => match load_rule_set(rules_path) {
+                Ok(rules) => rules,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            },
+            None => RuleSet::standard(),
+        };
+        let report = simulation.run(rules, &cards, evaluator);
+        display::show_simulation_report(&report);
     }
-    /// After a round is over, the winner chooses a prize, cards in the hands
-    /// and fields are shuffled back into the decks, and a new round will begin
-    fn complete_round(&mut self, win_state: WinState) {
-        let winning_player_index = win_state.player_index;
-        display::round_over(
-            self.players[winning_player_index].season(),
-            win_state.condition,
-        );
-
-        let prizes: Vec<&Card> = self
-            .players
-            .iter()
-            .map(|p| p.prize().as_ref().expect("No prize?"))
-            .collect();
-        let seasons: Vec<Season> = self.players.iter().map(|p| p.season()).collect();
-        let chosen_prize_index = display::choose_prize(winning_player_index, prizes, seasons);
-        if winning_player_index != chosen_prize_index {
-            let prize1 = self.players[winning_player_index].take_prize();
-            let prize2 = self.players[chosen_prize_index].take_prize();
-            self.players[winning_player_index].set_prize(prize2);
-            self.players[chosen_prize_index].set_prize(prize1);
+    /// Create the controllers for the seats. The computer players' choices are
+    /// seeded from the given seed.
+    fn controllers(
+        seats: Vec<Seat>,
+        seed: u64,
+        evaluator: Evaluator,
+    ) -> Vec<Box<dyn PlayerController>> {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        seats
+            .into_iter()
+            .map(|seat| seat.controller(seeds.gen(), evaluator))
+            .collect()
+    }
+    /// Offer to resume the saved game, if there is one. The computer players'
+    /// choices are seeded from the game's seed, as they were for the new game.
+    fn resume_saved_game(save_path: &Path, evaluator: Evaluator) -> Option<GameEngine> {
+        if !save_path.exists() || !display::confirm_resume_saved_game() {
+            return None;
         }
-
-        for i in 0..self.players.len() {
-            let player = &mut self.players[i];
-
-            player.move_hand_to_deck();
-            player.remove_cards_from_field();
-
-            let prize = player.take_prize();
-
-            // If the winning player swaps prizes and the received prize is
-            // of their season, it is added to their hand instead of their deck
-            if i == winning_player_index
-                && winning_player_index != chosen_prize_index
-                && prize.season() == player.season()
-            {
-                player.add_card_to_hand(prize);
-            } else {
-                player.add_card_to_deck(prize);
+        match load_game(save_path) {
+            Ok(saved_game) => {
+                display::game_resumed(saved_game.state.round());
+                let num_players = saved_game.state.num_players();
+                let seats = display::get_seats(&Season::at_table(num_players));
+                Some(GameEngine::resume(
+                    Self::controllers(seats, saved_game.log.seed(), evaluator),
+                    saved_game,
+                ))
             }
-
-            player.shuffle_deck();
-        }
-    }
-    /// Players take turns selecting a card to play and a location in which
-    /// to play it, and a win condition is checked based on the card that was played
-    /// for the player whose field the card was played in.
-    fn play_round(&mut self, first_player: usize) -> WinState {
-        assert!(first_player < self.num_players());
-        let mut player_index = first_player;
-
-        self.initialize_round();
-        loop {
-            let turn = self.player_takes_turn(player_index);
-            self.execute_turn(&turn);
-
-            if let Some(win_state) = self.check_for_win_conditions(&turn) {
-                return win_state;
+            Err(e) => {
+                eprintln!("{}", e);
+                None
             }
-            player_index = (player_index + 1) % self.num_players();
         }
     }
-    fn player_takes_turn(&self, player_index: usize) -> Turn {
-        display::wait_for_next_player(self.players[player_index].season());
-
-        let players_starting_with_self: Vec<&Player> = self
-            .players
-            .iter()
-            .cycle()
-            .skip(player_index)
-            .take(self.num_players())
-            .collect();
-        let fields: Vec<&Field> = players_starting_with_self
-            .iter()
-            .map(|p| p.field())
-            .collect();
-        let seasons: Vec<Season> = players_starting_with_self
-            .iter()
-            .map(|p| p.season())
-            .collect();
-        let hand = players_starting_with_self[0].hand();
-        display::show_all_fields(&fields);
-        loop {
-            let card_index_in_hand: usize = display::get_card_choice_from_hand(hand);
-            let selected_card = &hand[card_index_in_hand];
-            let valid_spots = turn::get_valid_spots_from_card(player_index, selected_card, &fields);
-            let possible_spot: Option<(usize, Spot)> = if selected_card.rune().ability().is_swap() {
-                display::select_spot_to_swap_card(
-                    selected_card,
-                    valid_spots,
-                    &fields,
-                    seasons.clone(),
-                )
-            } else {
-                display::select_spot_to_play_card(selected_card, &valid_spots[0])
-                    .map(|spot| (0, spot))
-            };
-            if let Some((field_index, spot_on_field)) = possible_spot {
-                return Turn {
-                    player_index,
-                    field_index,
-                    card_index_in_hand,
-                    spot_on_field,
-                };
-            }
+    /// Offer to save a replay of the finished game
+    fn offer_replay(log: &GameLog) {
+        let Some(path) = display::get_replay_path() else {
+            return;
+        };
+        let path = Path::new(&path).with_extension(REPLAY_EXTENSION);
+        match save_replay(&path, log) {
+            Ok(()) => display::replay_saved(&path.display().to_string()),
+            Err(e) => eprintln!("{}", e),
         }
     }
-    /// Players draw their hands up to 10 cards and flip the top card
-    /// of their decks to show their prize
-    fn initialize_round(&mut self) {
-        for player in &mut self.players {
-            player.fill_hand();
-            player.show_prize();
+    /// Load the rules from the rules file, or let the players pick a preset
+    /// when there isn't one
+    fn load_rules() -> Result<RuleSet, RuleSetError> {
+        let path = Path::new(RULES_PATH);
+        if path.exists() {
+            let rules = load_rule_set(path)?;
+            display::rules_loaded(RULES_PATH);
+            Ok(rules)
+        } else {
+            Ok(display::get_preset_rules())
         }
     }
-    /// Perform the play, removing the card from the player's hand and playing it
-    /// in the correct location
-    fn execute_turn(&mut self, turn: &Turn) {
-        let card = self.players[turn.player_index].take_card_from_hand(turn.card_index_in_hand);
-        let possible_other_card =
-            self.players[turn.field_index].play_card(card, turn.spot_on_field);
-        if let Some(other_card) = possible_other_card {
-            self.players[turn.player_index].add_card_to_hand(other_card);
-        }
+    /// Load the computer players' evaluation weights from their asset, falling
+    /// back to the built-in weights when the asset isn't there
+    fn load_evaluator() -> Result<Evaluator, WeightsError> {
+        let path = Path::new(WEIGHTS_PATH);
+        let weights = if path.exists() {
+            load_weights(path)?
+        } else {
+            Default::default()
+        };
+        Ok(Evaluator { weights })
     }
-    /// Check first for a game-winning condition, then for a round-winning condition
-    fn check_for_win_conditions(&self, turn: &Turn) -> Option<WinState> {
-        let field_index = turn.field_index;
-        let player_played_on = &self.players[field_index];
-        let field = player_played_on.field();
-        let spot = turn.spot_on_field;
-        let player_season = player_played_on.season();
-        let card = field
-            .get(spot)
-            .as_ref()
-            .expect("Should be a card here from excuting turn");
-
-        if card.season() == player_season {
-            let field_in_season = field.clone_in_season(player_season);
-
-            // If there is a win condition on the in-season field, then it is a game win
-            let opt_win_cond = check_win(&field_in_season, &spot, card);
-            if let Some(condition) = opt_win_cond {
-                return Some(WinState {
-                    player_index: field_index,
-                    game_won: true,
-                    condition,
-                });
-            }
-        }
-
-        let opt_win_cond = check_win(field, &spot, card);
-        if let Some(condition) = opt_win_cond {
-            let game_won = check_two_ancients_house_rule(&field.court, &condition, player_season);
-            Some(WinState {
-                player_index: field_index,
-                game_won,
-                condition,
-            })
+    /// Load the card set from the card list asset, falling back to the
+    /// built-in cards when the asset isn't there
+    fn load_cards() -> Result<Vec<Card>, CardListError> {
+        let path = Path::new(CARD_LIST_PATH);
+        if path.exists() {
+            load_card_list(path)
         } else {
-            None
+            Ok(all_cards())
         }
     }
 }
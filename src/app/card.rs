@@ -1,4 +1,5 @@
 pub(crate) mod ability;
+pub(crate) mod card_list;
 pub(crate) mod rune;
 pub(crate) mod score;
 
@@ -8,7 +9,9 @@ use score::*;
 
 use std::fmt::Display;
 
-use super::season::Season;
+use serde::{Deserialize, Serialize};
+
+use super::{field::Row, season::Season};
 
 pub(crate) fn all_cards() -> Vec<Card> {
     vec![
@@ -140,7 +143,64 @@ pub(crate) fn all_cards() -> Vec<Card> {
     ]
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+/// Every (garden, court) score pair that a card of the given season and rune
+/// may have. The card list is checked against this table, so scores that are
+/// not in it can only be used after changing it here.
+fn allowed_scores(season: Season, rune: Rune) -> Vec<(Score, Score)> {
+    let is_ferric = season == Season::Ferric;
+    let pick = |ferric: &[i32], seasonal: &[i32]| -> Vec<i32> {
+        if is_ferric {
+            ferric.to_vec()
+        } else {
+            seasonal.to_vec()
+        }
+    };
+    let value_pairs = |pairs: Vec<(i32, i32)>| {
+        pairs
+            .into_iter()
+            .map(|(garden, court)| (Score::Value(garden), Score::Value(court)))
+            .collect()
+    };
+    let same_in_both_rows =
+        |values: Vec<i32>| value_pairs(values.into_iter().map(|v| (v, v)).collect());
+    let modifier_in_both_rows =
+        |modifier: RowScoreModifier| vec![(Score::Mod(modifier), Score::Mod(modifier))];
+
+    match rune {
+        Rune::Ancient => same_in_both_rows(pick(&[12], &[10])),
+        Rune::Archer => same_in_both_rows(pick(&[8, 7], &[6, 5, 4])),
+        Rune::Beast if is_ferric => same_in_both_rows(vec![10, 9]),
+        Rune::Beast => value_pairs(vec![(12, 0), (10, 0), (8, 0)]),
+        // The seasonal Changelings are worth 1 in either row, as the card list
+        // has always had them. The cards built in code once had them at 0.
+        Rune::Changeling => same_in_both_rows(pick(&[2], &[1])),
+        Rune::Count => same_in_both_rows(pick(&[9], &[8])),
+        Rune::Countess => same_in_both_rows(pick(&[10], &[9])),
+        Rune::Magician => value_pairs(
+            pick(&[10, 9], &[9, 8, 7])
+                .into_iter()
+                .map(|court| (10 - court, court))
+                .collect(),
+        ),
+        Rune::Mist if is_ferric => modifier_in_both_rows(RowScoreModifier::Add(-1)),
+        Rune::Mist => vec![],
+        Rune::Plague => modifier_in_both_rows(RowScoreModifier::Mult(0)),
+        Rune::Queen => same_in_both_rows(pick(&[9, 7, 5], &[7, 5, 3])),
+        Rune::Warrior => value_pairs(
+            pick(&[10, 9], &[9, 8, 7])
+                .into_iter()
+                .map(|garden| (garden, 10 - garden))
+                .collect(),
+        ),
+        Rune::Weather if is_ferric => vec![],
+        Rune::Weather => modifier_in_both_rows(RowScoreModifier::Mult(2)),
+    }
+}
+
+/// Cards are saved as a row of the card list, e.g. `Spring,Beast,12,0`, so
+/// they are checked against the rules again when loaded
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub(crate) struct Card {
     season: Season,
     rune: Rune,
@@ -148,168 +208,96 @@ pub(crate) struct Card {
     garden_score: Score,
 }
 impl Card {
-    pub(crate) fn create_ancient(season: Season) -> Self {
-        let score = match season {
-            Season::Ferric => 12,
-            _ => 10,
-        };
-        let score = Score::Value(score);
-        Self {
-            season,
-            rune: Rune::Ancient,
-            garden_score: score,
-            court_score: score,
+    /// Create a card from its season, rune and scores, checking the scores
+    /// against the rules for that rune
+    pub(crate) fn new(
+        season: Season,
+        rune: Rune,
+        garden_score: Score,
+        court_score: Score,
+    ) -> Result<Self, String> {
+        if allowed_scores(season, rune).contains(&(garden_score, court_score)) {
+            Ok(Self {
+                season,
+                rune,
+                court_score,
+                garden_score,
+            })
+        } else {
+            Err(format!(
+                "A {} {} cannot have scores {}/{}",
+                season, rune, garden_score, court_score
+            ))
         }
     }
-    pub(crate) fn create_archer(season: Season, score: i32) -> Self {
-        match season {
-            Season::Ferric => assert!(score == 7 || score == 8),
-            _ => assert!(score < 7 && score > 4),
-        };
-        let score = Score::Value(score);
+    /// Create the only card of the given season and rune
+    fn create_only(season: Season, rune: Rune) -> Self {
+        let scores = allowed_scores(season, rune);
+        assert_eq!(1, scores.len(), "There is no single {} {}", season, rune);
+        let (garden_score, court_score) = scores[0];
         Self {
             season,
-            rune: Rune::Archer,
-            garden_score: score,
-            court_score: score,
+            rune,
+            court_score,
+            garden_score,
         }
     }
-    pub(crate) fn create_beast(season: Season, garden_score: i32) -> Self {
-        let court_score = match season {
-            Season::Ferric => {
-                assert!(garden_score == 10 || garden_score == 9);
-                garden_score
-            }
-            _ => {
-                assert!(garden_score == 12 || garden_score == 10 || garden_score == 8);
-                0
-            }
-        };
-        let garden_score = Score::Value(garden_score);
-        let court_score = Score::Value(court_score);
+    /// Create the card of the given season and rune identified by one of its scores
+    fn create_with_score(season: Season, rune: Rune, score: i32, row: Row) -> Self {
+        let (garden_score, court_score) = allowed_scores(season, rune)
+            .into_iter()
+            .find(|(garden_score, court_score)| match row {
+                Row::Garden => *garden_score == Score::Value(score),
+                Row::Court => *court_score == Score::Value(score),
+            })
+            .unwrap_or_else(|| {
+                panic!(
+                    "A {} {} cannot have a {} score of {}",
+                    season, rune, row, score
+                )
+            });
         Self {
             season,
-            rune: Rune::Beast,
-            garden_score,
+            rune,
             court_score,
+            garden_score,
         }
     }
+    pub(crate) fn create_ancient(season: Season) -> Self {
+        Self::create_only(season, Rune::Ancient)
+    }
+    pub(crate) fn create_archer(season: Season, score: i32) -> Self {
+        Self::create_with_score(season, Rune::Archer, score, Row::Garden)
+    }
+    pub(crate) fn create_beast(season: Season, garden_score: i32) -> Self {
+        Self::create_with_score(season, Rune::Beast, garden_score, Row::Garden)
+    }
     pub(crate) fn create_changeling(season: Season) -> Self {
-        let score = match season {
-            Season::Ferric => 2,
-            _ => 0,
-        };
-        let score = Score::Value(score);
-        Self {
-            season,
-            rune: Rune::Changeling,
-            court_score: score,
-            garden_score: score,
-        }
+        Self::create_only(season, Rune::Changeling)
     }
     pub(crate) fn create_count(season: Season) -> Self {
-        let score = match season {
-            Season::Ferric => 9,
-            _ => 8,
-        };
-        let score = Score::Value(score);
-        Self {
-            season,
-            rune: Rune::Count,
-            court_score: score,
-            garden_score: score,
-        }
+        Self::create_only(season, Rune::Count)
     }
     pub(crate) fn create_countess(season: Season) -> Self {
-        let score = match season {
-            Season::Ferric => 10,
-            _ => 9,
-        };
-        let score = Score::Value(score);
-        Self {
-            season,
-            rune: Rune::Countess,
-            court_score: score,
-            garden_score: score,
-        }
+        Self::create_only(season, Rune::Countess)
     }
     pub(crate) fn create_magician(season: Season, court_score: i32) -> Self {
-        let garden_score = match season {
-            Season::Ferric => {
-                assert!(court_score == 10 || court_score == 9);
-                10 - court_score
-            }
-            _ => {
-                assert!(court_score == 9 || court_score == 8 || court_score == 7);
-                10 - court_score
-            }
-        };
-        let garden_score = Score::Value(garden_score);
-        let court_score = Score::Value(court_score);
-        Self {
-            season,
-            rune: Rune::Magician,
-            garden_score,
-            court_score,
-        }
+        Self::create_with_score(season, Rune::Magician, court_score, Row::Court)
     }
     pub(crate) fn create_mist() -> Self {
-        Self {
-            season: Season::Ferric,
-            rune: Rune::Mist,
-            garden_score: Score::Mod(RowScoreModifier::Add(-1)),
-            court_score: Score::Mod(RowScoreModifier::Add(-1)),
-        }
+        Self::create_only(Season::Ferric, Rune::Mist)
     }
     pub(crate) fn create_plague(season: Season) -> Self {
-        Self {
-            season,
-            rune: Rune::Plague,
-            garden_score: Score::Mod(RowScoreModifier::Mult(0)),
-            court_score: Score::Mod(RowScoreModifier::Mult(0)),
-        }
+        Self::create_only(season, Rune::Plague)
     }
     pub(crate) fn create_queen(season: Season, score: i32) -> Self {
-        match season {
-            Season::Ferric => assert!(score == 9 || score == 7 || score == 5),
-            _ => assert!(score == 7 || score == 5 || score == 3),
-        };
-        let score = Score::Value(score);
-        Self {
-            season,
-            rune: Rune::Queen,
-            garden_score: score,
-            court_score: score,
-        }
+        Self::create_with_score(season, Rune::Queen, score, Row::Garden)
     }
     pub(crate) fn create_warrior(season: Season, garden_score: i32) -> Self {
-        let court_score = match season {
-            Season::Ferric => {
-                assert!(garden_score == 10 || garden_score == 9);
-                10 - garden_score
-            }
-            _ => {
-                assert!(garden_score == 9 || garden_score == 8 || garden_score == 7);
-                10 - garden_score
-            }
-        };
-        let garden_score = Score::Value(garden_score);
-        let court_score = Score::Value(court_score);
-        Self {
-            season,
-            rune: Rune::Warrior,
-            garden_score,
-            court_score,
-        }
+        Self::create_with_score(season, Rune::Warrior, garden_score, Row::Garden)
     }
     pub(crate) fn create_weather(season: Season) -> Self {
-        let score = Score::Mod(RowScoreModifier::Mult(2));
-        Self {
-            season,
-            rune: Rune::Weather,
-            court_score: score,
-            garden_score: score,
-        }
+        Self::create_only(season, Rune::Weather)
     }
 
     pub(crate) fn season(&self) -> Season {
@@ -325,7 +313,7 @@ impl Card {
         self.garden_score
     }
 
-    pub(crate) fn to_text(&self) -> String {
+    pub(crate) fn to_text(self) -> String {
         format!(
             "{} {} {}/{}",
             self.season, self.rune, self.garden_score, self.court_score
@@ -343,6 +331,23 @@ impl Card {
         }
     }
 }
+impl From<Card> for String {
+    fn from(card: Card) -> Self {
+        format!(
+            "{},{},{},{}",
+            card.season,
+            card.rune,
+            card.garden_score.to_notation(),
+            card.court_score.to_notation()
+        )
+    }
+}
+impl TryFrom<String> for Card {
+    type Error = String;
+    fn try_from(row: String) -> Result<Self, Self::Error> {
+        card_list::parse_card(&row)
+    }
+}
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match (self.garden_score, self.court_score) {
@@ -19,9 +19,6 @@ pub(crate) enum Ability {
 }
 impl Ability {
     pub(crate) fn is_swap(&self) -> bool {
-        match self {
-            Ability::Swap => true,
-            _ => false,
-        }
+        matches!(self, Ability::Swap)
     }
 }
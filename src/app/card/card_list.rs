@@ -0,0 +1,155 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+use super::{super::season::Season, rune::Rune, score::Score, Card};
+
+/// The card list that ships with the game
+pub(crate) const CARD_LIST_PATH: &str = "assets/card_list.csv";
+
+const HEADER: [&str; 4] = ["Season", "Rune", "GardenScore", "CourtScore"];
+
+#[derive(Debug)]
+pub(crate) enum CardListError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Invalid {
+        path: String,
+        line: usize,
+        message: String,
+    },
+}
+impl Display for CardListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardListError::Io { path, error } => write!(f, "Could not read {}: {}", path, error),
+            CardListError::Invalid {
+                path,
+                line,
+                message,
+            } => write!(f, "{}, line {}: {}", path, line, message),
+        }
+    }
+}
+impl std::error::Error for CardListError {}
+
+/// Load a card set from a CSV file with the columns Season, Rune, GardenScore,
+/// and CourtScore.
+///
+/// The card set can be changed without recompiling only within the scores
+/// built into the game: cards can be added, removed or repeated, but every
+/// row must have scores its season and rune may have (see `Card::new`). A
+/// card with new scores, such as a 13-point Ancient, needs the score table in
+/// `card.rs` changed too.
+pub(crate) fn load_card_list(path: &Path) -> Result<Vec<Card>, CardListError> {
+    let path_name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|error| CardListError::Io {
+        path: path_name.clone(),
+        error,
+    })?;
+    parse_card_list(&text, &path_name)
+}
+
+/// Parse the text of a card list. Each row is checked against the rules for
+/// its rune, and blank lines are skipped.
+fn parse_card_list(text: &str, path: &str) -> Result<Vec<Card>, CardListError> {
+    let invalid = |line: usize, message: String| CardListError::Invalid {
+        path: path.to_string(),
+        line,
+        message,
+    };
+    let mut lines = text.lines().enumerate().map(|(i, l)| (i + 1, l.trim()));
+
+    let header: Vec<&str> = match lines.next() {
+        Some((_, header)) => header.split(',').map(str::trim).collect(),
+        None => vec![],
+    };
+    if header != HEADER {
+        return Err(invalid(
+            1,
+            format!("Expected the header {}", HEADER.join(",")),
+        ));
+    }
+
+    lines
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(line, l)| parse_card(l).map_err(|message| invalid(line, message)))
+        .collect()
+}
+
+/// Parse one row of the card list into a card
+pub(crate) fn parse_card(row: &str) -> Result<Card, String> {
+    let columns: Vec<&str> = row.split(',').map(str::trim).collect();
+    let [season, rune, garden_score, court_score] = columns[..] else {
+        return Err(format!(
+            "Expected {} columns, found {}",
+            HEADER.len(),
+            columns.len()
+        ));
+    };
+    let season = Season::from_str(season).map_err(|_| format!("Unknown season '{}'", season))?;
+    let rune = Rune::from_str(rune).map_err(|_| format!("Unknown rune '{}'", rune))?;
+    Card::new(
+        season,
+        rune,
+        Score::from_str(garden_score)?,
+        Score::from_str(court_score)?,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{all_cards, score::RowScoreModifier};
+    use super::*;
+
+    #[test]
+    fn test_card_list_asset_matches_all_cards() {
+        let cards = parse_card_list(
+            include_str!("../../../assets/card_list.csv"),
+            CARD_LIST_PATH,
+        )
+        .expect("The card list asset should be valid");
+        assert_eq!(all_cards(), cards);
+    }
+
+    #[test]
+    fn test_score_notation() {
+        assert_eq!(Ok(Score::Value(12)), Score::from_str("12"));
+        assert_eq!(
+            Ok(Score::Mod(RowScoreModifier::Add(-1))),
+            Score::from_str("R-1")
+        );
+        assert_eq!(
+            Ok(Score::Mod(RowScoreModifier::Mult(0))),
+            Score::from_str("Rx0")
+        );
+        assert!(Score::from_str("R1").is_err());
+        assert!(Score::from_str("x2").is_err());
+    }
+
+    #[test]
+    fn test_invalid_row_reports_line_number() {
+        let text =
+            "Season,Rune,GardenScore,CourtScore\nSpring,Ancient,10,10\n\nSpring,Archer,8,8\n";
+        let error = parse_card_list(text, "cards.csv").unwrap_err();
+        assert_eq!(
+            "cards.csv, line 4: A Spring Archer cannot have scores 8/8",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_seasonal_changelings_score_one() {
+        let changeling = parse_card("Summer,Changeling,1,1").expect("Should parse");
+        assert_eq!(Card::create_changeling(Season::Summer), changeling);
+        assert_eq!(Score::Value(1), changeling.garden_score());
+        assert!(parse_card("Summer,Changeling,0,0").is_err());
+        assert!(parse_card("Ferric,Changeling,2,2").is_ok());
+    }
+
+    #[test]
+    fn test_missing_header_is_rejected() {
+        let error = parse_card_list("Spring,Ancient,10,10\n", "cards.csv").unwrap_err();
+        assert!(matches!(error, CardListError::Invalid { line: 1, .. }));
+    }
+}
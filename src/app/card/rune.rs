@@ -1,8 +1,8 @@
-use strum_macros::Display;
+use strum_macros::{Display, EnumString};
 
 use super::Ability;
 
-#[derive(Clone, Copy, Debug, PartialEq, Display)]
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumString)]
 pub(crate) enum Rune {
     Ancient,
     Archer,
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) enum Score {
@@ -29,3 +29,32 @@ impl Display for RowScoreModifier {
         format!("Row {}", op).fmt(f)
     }
 }
+impl Score {
+    /// Write the score in the notation used in the card list
+    pub(crate) fn to_notation(self) -> String {
+        match self {
+            Score::Value(v) => v.to_string(),
+            Score::Mod(RowScoreModifier::Add(a)) => format!("R{a:+}"),
+            Score::Mod(RowScoreModifier::Mult(a)) => format!("Rx{a}"),
+        }
+    }
+}
+/// Parse the notation used in the card list: a plain number for a card's own
+/// value, `R-1`/`R+1` for a row addition, or `Rx2` for a row multiplier
+impl FromStr for Score {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid score '{}'", s);
+        let Some(modifier) = s.strip_prefix('R') else {
+            return s.parse().map(Score::Value).map_err(|_| invalid());
+        };
+        let modifier = if let Some(factor) = modifier.strip_prefix('x') {
+            RowScoreModifier::Mult(factor.parse().map_err(|_| invalid())?)
+        } else if modifier.starts_with(['+', '-']) {
+            RowScoreModifier::Add(modifier.parse().map_err(|_| invalid())?)
+        } else {
+            return Err(invalid());
+        };
+        Ok(Score::Mod(modifier))
+    }
+}
@@ -0,0 +1,128 @@
+use super::{
+    card::{rune::Rune, Card},
+    season::Season,
+    state::GameState,
+};
+
+/// How many of the cards of one season and rune a player has seen, and how
+/// many are still hidden from them
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct TrackedCards {
+    pub season: Season,
+    pub rune: Rune,
+    /// On any field, in the player's own hand, or showing as a prize
+    pub seen: usize,
+    /// In the other players' hands, or in the decks and discard piles
+    pub hidden: usize,
+}
+
+/// What one player can tell of where the cards are, by season and rune. Each
+/// player has their own, since each of them sees a different hand.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct CardTracker {
+    seat: usize,
+    /// In season then rune order, for only the seasons and runes the game
+    /// has cards of
+    counts: Vec<TrackedCards>,
+}
+impl CardTracker {
+    /// Start tracking for the player at `seat` the cards the game was dealt
+    pub(crate) fn new(seat: usize, cards: &[Card]) -> Self {
+        let mut counts: Vec<TrackedCards> = vec![];
+        for card in cards {
+            match counts
+                .iter_mut()
+                .find(|c| c.season == card.season() && c.rune == card.rune())
+            {
+                Some(count) => count.hidden += 1,
+                None => counts.push(TrackedCards {
+                    season: card.season(),
+                    rune: card.rune(),
+                    seen: 0,
+                    hidden: 1,
+                }),
+            }
+        }
+        counts.sort_by_key(|c| (c.season as usize, c.rune as usize));
+        Self { seat, counts }
+    }
+    /// Start tracking for the player at `seat` the cards of a game already
+    /// under way, such as a resumed one
+    pub(crate) fn of_game(seat: usize, state: &GameState) -> Self {
+        let mut cards = state.seen_cards(seat);
+        cards.extend(state.unseen_cards(seat));
+        let mut tracker = Self::new(seat, &cards);
+        tracker.update(state);
+        tracker
+    }
+    /// Count again the cards the player can see
+    pub(crate) fn update(&mut self, state: &GameState) {
+        for count in self.counts.iter_mut() {
+            count.hidden += count.seen;
+            count.seen = 0;
+        }
+        for card in state.seen_cards(self.seat) {
+            if let Some(count) = self
+                .counts
+                .iter_mut()
+                .find(|c| c.season == card.season() && c.rune == card.rune())
+            {
+                count.seen += 1;
+                count.hidden -= 1;
+            }
+        }
+    }
+    pub(crate) fn counts(&self) -> &[TrackedCards] {
+        &self.counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::super::{card::all_cards, rules::RuleSet};
+    use super::*;
+
+    #[test]
+    fn test_tracks_only_what_the_player_can_see() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut state = GameState::new_game(2, RuleSet::standard(), all_cards(), &mut rng, &mut ());
+        let mut spring = CardTracker::new(0, &all_cards());
+        spring.update(&state);
+        let mut autumn = CardTracker::of_game(1, &state);
+
+        let total = |tracker: &CardTracker, f: fn(&TrackedCards) -> usize| -> usize {
+            tracker.counts().iter().map(f).sum()
+        };
+        assert_eq!(120, total(&spring, |c| c.seen + c.hidden));
+        assert_eq!(state.seen_cards(0).len(), total(&spring, |c| c.seen));
+        assert_eq!(state.unseen_cards(0).len(), total(&spring, |c| c.hidden));
+        // Each sees their own hand, and not the other's
+        assert_ne!(spring.counts(), autumn.counts());
+        assert_eq!(
+            spring
+                .counts()
+                .iter()
+                .map(|c| c.seen + c.hidden)
+                .collect::<Vec<_>>(),
+            autumn
+                .counts()
+                .iter()
+                .map(|c| c.seen + c.hidden)
+                .collect::<Vec<_>>()
+        );
+
+        // Spring's first card goes onto their own field, where Autumn sees it
+        let seen_before = total(&autumn, |c| c.seen);
+        let turn = *state
+            .legal_turns()
+            .iter()
+            .find(|t| t.field_index == 0)
+            .expect("Spring can play on their own field");
+        state.apply_turn(&turn, &mut ()).expect("The turn is legal");
+        autumn.update(&state);
+        assert_eq!(seen_before + 1, total(&autumn, |c| c.seen));
+        assert_eq!(state.unseen_cards(1).len(), total(&autumn, |c| c.hidden));
+    }
+}
@@ -0,0 +1,167 @@
+pub(crate) mod greedy;
+pub(crate) mod mcts;
+pub(crate) mod random;
+pub(crate) mod terminal;
+
+use strum_macros::Display;
+
+use super::{
+    card_tracker::CardTracker,
+    evaluation::Evaluator,
+    season::Season,
+    state::{GameState, Pass},
+    tactics::best_prize,
+    turn::{RuleError, Turn},
+};
+
+/// What a player decided to do when asked for their turn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Action {
+    /// Play a card
+    Play(Turn),
+    /// Take back the last turn
+    Undo,
+    /// Play the last turn that was taken back again
+    Redo,
+    /// Stop the game here so it can be saved and resumed later
+    SaveAndQuit,
+}
+
+/// How a player answered when asked to start their turn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Acknowledgement {
+    Ready,
+    /// Take back the turn played just before, instead of starting
+    Undo,
+}
+
+/// The actions besides playing a card that are open to the player choosing a
+/// turn
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct TurnOptions {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// How hard a computer player tries to win. The easier levels misplay on
+/// purpose now and then, so new players aren't crushed.
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
+pub(crate) enum Difficulty {
+    /// Misses half of its wins, and plays at random a third of the time
+    #[strum(to_string = "Beginner")]
+    Beginner,
+    /// Now and then misses a win or plays at random
+    #[strum(to_string = "Normal")]
+    Normal,
+    /// Always plays the turn that looks best one turn ahead
+    #[strum(to_string = "Hard")]
+    Hard,
+    /// Plays out many games for each turn
+    #[strum(to_string = "Expert")]
+    Expert(mcts::Budget),
+}
+
+/// What a computer player goes for, besides winning
+#[derive(Clone, Copy, PartialEq, Debug, Default, Display)]
+pub(crate) enum Style {
+    /// Goes for whichever win is nearest
+    #[default]
+    #[strum(to_string = "Balanced")]
+    Balanced,
+    /// Swaps Plagues and Mists onto the leading player's field, and works
+    /// harder at breaking up the other players' wins
+    #[strum(to_string = "Aggressive (attacks the leader)")]
+    Aggressive,
+    /// Builds court sets for the Count/Countess and three in the court wins
+    #[strum(to_string = "Builder (goes for court sets)")]
+    Builder,
+    /// Piles up points for the points win
+    #[strum(to_string = "Point-chaser (goes for points)")]
+    PointChaser,
+}
+
+/// The settings of a computer player
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct BotSettings {
+    pub difficulty: Difficulty,
+    pub style: Style,
+}
+impl BotSettings {
+    fn controller(self, seed: u64, evaluator: Evaluator) -> Box<dyn PlayerController> {
+        let greedy = |mistakes| -> Box<dyn PlayerController> {
+            Box::new(greedy::GreedyBot::new(
+                seed, self.style, mistakes, evaluator,
+            ))
+        };
+        match self.difficulty {
+            Difficulty::Beginner => greedy(greedy::Mistakes {
+                missed_wins: 0.5,
+                random_turns: 0.3,
+            }),
+            Difficulty::Normal => greedy(greedy::Mistakes {
+                missed_wins: 0.15,
+                random_turns: 0.1,
+            }),
+            Difficulty::Hard => greedy(greedy::Mistakes::NONE),
+            Difficulty::Expert(budget) => {
+                Box::new(mcts::MctsBot::new(seed, budget, self.style, evaluator))
+            }
+        }
+    }
+}
+
+/// Who makes the decisions for a seat at the table
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
+pub(crate) enum Seat {
+    #[strum(to_string = "Human")]
+    Human,
+    #[strum(to_string = "Computer (random moves)")]
+    Random,
+    #[strum(to_string = "Computer")]
+    Computer(BotSettings),
+}
+impl Seat {
+    /// Create the controller for the seat. A computer player makes its
+    /// choices from the given seed, and weighs up positions with the given
+    /// evaluator.
+    pub(crate) fn controller(self, seed: u64, evaluator: Evaluator) -> Box<dyn PlayerController> {
+        match self {
+            Seat::Human => Box::new(terminal::TerminalController::default()),
+            Seat::Random => Box::new(random::RandomBot::new(seed)),
+            Seat::Computer(settings) => settings.controller(seed, evaluator),
+        }
+    }
+}
+
+/// Makes the decisions for one seat at the table. The game engine asks a
+/// player's controller whenever that player has to act, so the rules can run
+/// with humans at a terminal, bots, or scripted test players alike.
+pub(crate) trait PlayerController {
+    /// Called at the start of the player's turn, before they choose a play,
+    /// and before a round's winner chooses their prize. If `can_undo`, the
+    /// turn just played may still be taken back instead.
+    fn acknowledge_turn(&mut self, season: Season, can_undo: bool) -> Acknowledgement;
+    /// Choose a card from the hand of the player at `player_index` and the
+    /// field and spot to play it in, or one of the other `options`
+    fn choose_turn(
+        &mut self,
+        state: &GameState,
+        player_index: usize,
+        options: TurnOptions,
+    ) -> Action;
+    /// After the player at `player_index` wins a round, choose the index of the
+    /// player whose prize to take. Unless a controller chooses otherwise, it
+    /// takes the prize that is assessed to be worth the most.
+    fn choose_prize(&mut self, state: &GameState, player_index: usize) -> usize {
+        best_prize(state).unwrap_or(player_index)
+    }
+    /// Called when the player had no legal turn, so they passed by the rules
+    /// for having no legal turn
+    fn turn_passed(&mut self, _season: Season, _pass: &Pass) {}
+    /// Called when the engine rejects a turn or prize choice for breaking the
+    /// rules, before asking for the decision again
+    fn decision_rejected(&mut self, _error: &RuleError) {}
+    /// Called after every change to the game with what the player can tell of
+    /// where the cards are
+    fn cards_tracked(&mut self, _tracker: &CardTracker) {}
+}
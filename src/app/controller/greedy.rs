@@ -0,0 +1,342 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{
+    super::{
+        card::rune::Rune,
+        evaluation::Evaluator,
+        field::Row,
+        score_breakdown::ScoreBreakdown,
+        season::Season,
+        state::GameState,
+        tactics::{assess_turns, explain_turn, Suggestion, TurnAssessment},
+    },
+    Acknowledgement, Action, PlayerController, Style, TurnOptions,
+};
+
+/// How often a computer player misplays on purpose
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Mistakes {
+    /// The chance of overlooking its own wins, when it has one
+    pub missed_wins: f64,
+    /// The chance of playing a random legal turn instead of thinking
+    pub random_turns: f64,
+}
+impl Mistakes {
+    pub(crate) const NONE: Self = Self {
+        missed_wins: 0.0,
+        random_turns: 0.0,
+    };
+}
+
+/// A computer player that looks one turn ahead. It takes any win it can,
+/// keeps the next player from winning on their turn, breaks up the other
+/// players' near wins, and otherwise builds towards a win of its own.
+pub(crate) struct GreedyBot {
+    /// For choosing between turns that look equally good, and when to misplay
+    rng: StdRng,
+    style: Style,
+    mistakes: Mistakes,
+    /// For choosing between turns that are alike by its tactics
+    evaluator: Evaluator,
+}
+impl GreedyBot {
+    pub(crate) fn new(seed: u64, style: Style, mistakes: Mistakes, evaluator: Evaluator) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            style,
+            mistakes,
+            evaluator,
+        }
+    }
+}
+impl PlayerController for GreedyBot {
+    fn acknowledge_turn(&mut self, _season: Season, _can_undo: bool) -> Acknowledgement {
+        Acknowledgement::Ready
+    }
+    fn choose_turn(
+        &mut self,
+        state: &GameState,
+        player_index: usize,
+        _options: TurnOptions,
+    ) -> Action {
+        if self.rng.gen_bool(self.mistakes.random_turns) {
+            if let Some(turn) = state.legal_turns().choose(&mut self.rng) {
+                return Action::Play(*turn);
+            }
+        }
+        let mut assessments = assess_turns(state);
+        let is_own_win = |a: &TurnAssessment| {
+            a.win
+                .as_ref()
+                .is_some_and(|w| w.player_index == player_index)
+        };
+        if assessments.iter().any(is_own_win)
+            && !assessments.iter().all(is_own_win)
+            && self.rng.gen_bool(self.mistakes.missed_wins)
+        {
+            assessments.retain(|a| !is_own_win(a));
+        }
+        let scores: Vec<f64> = assessments
+            .iter()
+            .map(|a| greedy_score(a, state, self.style))
+            .collect();
+        let best_score = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let best: Vec<&TurnAssessment> = assessments
+            .iter()
+            .zip(&scores)
+            .filter(|(_, &score)| score == best_score)
+            .map(|(a, _)| a)
+            .collect();
+        // Break ties by how the position looks after the turn
+        let evaluations: Vec<f64> = best
+            .iter()
+            .map(|a| {
+                let mut after = state.clone();
+                after
+                    .apply_turn(&a.turn, &mut ())
+                    .expect("Legal turns can be played");
+                self.evaluator.evaluate(&after, player_index)
+            })
+            .collect();
+        let best_evaluation = evaluations
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let best: Vec<&TurnAssessment> = best
+            .into_iter()
+            .zip(&evaluations)
+            .filter(|(_, &evaluation)| evaluation == best_evaluation)
+            .map(|(a, _)| a)
+            .collect();
+        let assessment = best
+            .choose(&mut self.rng)
+            .expect("A player with no legal turn passes without being asked");
+        Action::Play(assessment.turn)
+    }
+}
+
+/// How much a greedy player likes the current player's turn. Its own wins
+/// come first, with game wins above round wins, and a win for anyone else
+/// comes last. Otherwise a card that would let the next player win counts
+/// against the turn twice as much as one for the players after them, and
+/// four times as much as a card in its own hand that would win next turn
+/// counts for it. The player's style then adds to the turns it likes.
+pub(super) fn greedy_score(assessment: &TurnAssessment, state: &GameState, style: Style) -> f64 {
+    let player_index = state.current_player();
+    match &assessment.win {
+        Some(win_state) if win_state.player_index != player_index => return -1e6,
+        Some(win_state) if win_state.game_won => return 2e6,
+        Some(_) => return 1e6,
+        None => {}
+    }
+    let (own_weight, rival_weight) = match style {
+        Style::Aggressive => (1.0, 1.5),
+        Style::Builder => (2.0, 1.0),
+        Style::Balanced | Style::PointChaser => (1.0, 1.0),
+    };
+    let next_player = (player_index + 1) % state.num_players();
+    let threats: f64 = assessment
+        .threats
+        .iter()
+        .enumerate()
+        .map(|(i, &threats)| {
+            let weight = if i == player_index {
+                own_weight
+            } else if i == next_player {
+                -4.0 * rival_weight
+            } else {
+                -2.0 * rival_weight
+            };
+            weight * threats as f64
+        })
+        .sum();
+    threats + style_bonus(assessment, state, style)
+}
+
+/// The `count` turns the greedy tactics like best for the current player,
+/// best first, with what each of them does
+pub(crate) fn suggest_turns(state: &GameState, count: usize) -> Vec<Suggestion> {
+    let mut scored: Vec<(TurnAssessment, f64)> = assess_turns(state)
+        .into_iter()
+        .map(|a| {
+            let score = greedy_score(&a, state, Style::Balanced);
+            (a, score)
+        })
+        .collect();
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scored
+        .into_iter()
+        .take(count)
+        .map(|(a, _)| Suggestion {
+            turn: a.turn,
+            card: a.card,
+            reasons: explain_turn(state, &a),
+        })
+        .collect()
+}
+
+/// What a turn is worth to a player's style, on top of its threats
+fn style_bonus(assessment: &TurnAssessment, state: &GameState, style: Style) -> f64 {
+    let player_index = state.current_player();
+    let turn = &assessment.turn;
+    match style {
+        Style::Balanced => 0.0,
+        Style::Aggressive => {
+            // The leader is the other player with the most points on their
+            // field before the turn
+            let leader = (0..state.num_players())
+                .filter(|&i| i != player_index)
+                .max_by_key(|&i| ScoreBreakdown::of_field(state.players()[i].field()).total());
+            let is_attack = matches!(assessment.card.rune(), Rune::Plague | Rune::Mist);
+            if is_attack && leader == Some(turn.field_index) {
+                2.0
+            } else {
+                0.0
+            }
+        }
+        Style::Builder => {
+            let is_court_set_card = matches!(
+                assessment.card.rune(),
+                Rune::Ancient
+                    | Rune::Beast
+                    | Rune::Changeling
+                    | Rune::Count
+                    | Rune::Countess
+                    | Rune::Queen
+            );
+            if is_court_set_card
+                && turn.field_index == player_index
+                && *turn.spot_on_field.row() == Row::Court
+            {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Style::PointChaser => assessment.points[player_index] as f64 / 5.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{
+        card::{all_cards, rune::Rune, Card},
+        engine::{GameEnd, GameEngine},
+        field::{Row, Spot},
+        rules::RuleSet,
+        state::Outcome,
+        turn::Turn,
+    };
+    use super::super::{BotSettings, Difficulty, Seat};
+    use super::*;
+
+    fn chosen_turn(state: &GameState) -> Turn {
+        chosen_turn_with(state, Style::Balanced, Mistakes::NONE)
+    }
+
+    fn chosen_turn_with(state: &GameState, style: Style, mistakes: Mistakes) -> Turn {
+        match GreedyBot::new(0, style, mistakes, Evaluator::default()).choose_turn(
+            state,
+            0,
+            TurnOptions::default(),
+        ) {
+            Action::Play(turn) => turn,
+            action => panic!("Expected a turn, found {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_takes_a_win() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        spring.add_card_to_hand(Card::create_countess(Season::Ferric));
+
+        let turn = chosen_turn(&state);
+        assert_eq!(1, turn.card_index_in_hand);
+        assert_eq!(Row::Court, *turn.spot_on_field.row());
+    }
+
+    #[test]
+    fn test_beginners_can_miss_a_win() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        spring.add_card_to_hand(Card::create_countess(Season::Ferric));
+
+        let mistakes = Mistakes {
+            missed_wins: 1.0,
+            random_turns: 0.0,
+        };
+        let turn = chosen_turn_with(&state, Style::Balanced, mistakes);
+        let outcome = state.clone().apply_turn(&turn, &mut ());
+        assert!(matches!(outcome, Ok(Outcome::NextTurn)));
+    }
+
+    #[test]
+    fn test_styles_choose_different_turns() {
+        let mut state = GameState::new(3, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.add_card_to_hand(Card::create_count(Season::Spring));
+        spring.add_card_to_hand(Card::create_warrior(Season::Ferric, 10));
+        spring.add_card_to_hand(Card::create_mist());
+        state.players_mut()[1].play_card(
+            Card::create_queen(Season::Summer, 3),
+            Spot::new(Row::Garden, 0),
+        );
+        state.players_mut()[2].play_card(
+            Card::create_beast(Season::Winter, 8),
+            Spot::new(Row::Garden, 0),
+        );
+        let card_played = |turn: Turn| state.players()[0].hand()[turn.card_index_in_hand].rune();
+
+        let builder = chosen_turn_with(&state, Style::Builder, Mistakes::NONE);
+        assert_eq!(Rune::Count, card_played(builder));
+        assert_eq!(0, builder.field_index);
+        assert_eq!(Row::Court, *builder.spot_on_field.row());
+
+        let point_chaser = chosen_turn_with(&state, Style::PointChaser, Mistakes::NONE);
+        assert_eq!(Rune::Warrior, card_played(point_chaser));
+        assert_eq!(0, point_chaser.field_index);
+
+        // The Winter player leads on points
+        let aggressive = chosen_turn_with(&state, Style::Aggressive, Mistakes::NONE);
+        assert_eq!(Rune::Mist, card_played(aggressive));
+        assert_eq!(2, aggressive.field_index);
+    }
+
+    #[test]
+    fn test_breaks_up_the_next_players_court() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        spring.add_card_to_hand(Card::create_mist());
+        let autumn = &mut state.players_mut()[1];
+        autumn.play_card(Card::create_count(Season::Autumn), Spot::new(Row::Court, 2));
+        autumn.set_deck(vec![Card::create_countess(Season::Ferric); 3]);
+
+        let turn = chosen_turn(&state);
+        assert_eq!(
+            Rune::Mist,
+            state.players()[0].hand()[turn.card_index_in_hand].rune()
+        );
+        assert_eq!(1, turn.field_index);
+        assert_eq!(Spot::new(Row::Court, 2), turn.spot_on_field);
+    }
+
+    #[test]
+    fn test_greedy_bot_plays_a_whole_game() {
+        let settings = BotSettings {
+            difficulty: Difficulty::Hard,
+            style: Style::Balanced,
+        };
+        let controllers = vec![
+            Seat::Computer(settings).controller(0, Evaluator::default()),
+            Seat::Random.controller(1, Evaluator::default()),
+        ];
+        let mut engine = GameEngine::new(controllers, RuleSet::standard(), all_cards(), 4);
+        assert!(matches!(engine.run(), GameEnd::Won(_)));
+    }
+}
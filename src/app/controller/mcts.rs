@@ -0,0 +1,424 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use super::{
+    super::{
+        card::Card,
+        evaluation::Evaluator,
+        field::Spot,
+        season::Season,
+        state::{GameState, Phase},
+        tactics::{assess_turns, winning_play},
+        turn::Turn,
+    },
+    greedy::greedy_score,
+    Acknowledgement, Action, PlayerController, Style, TurnOptions,
+};
+
+/// How much searching a computer player may do for each turn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Budget {
+    /// Play out this many games
+    Iterations(u32),
+    /// Play out games until this much time has passed
+    Time(Duration),
+}
+impl Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Budget::Iterations(n) => write!(f, "{} playouts per turn", n),
+            Budget::Time(duration) => write!(f, "{:?} per turn", duration),
+        }
+    }
+}
+
+/// The search a computer player does when nobody says otherwise
+pub(crate) const DEFAULT_BUDGET: Budget = Budget::Iterations(2000);
+
+/// How strongly the search favours turns it has tried less often over turns
+/// that have won more often
+const EXPLORATION: f64 = 0.7;
+/// What winning a round is worth to the search, next to winning the game
+const ROUND_WIN_VALUE: f64 = 0.5;
+/// How far the greedy tactics' opinion of a move can raise or lower it, before
+/// it has been played out
+const GREEDY_BIAS: f64 = 0.5;
+/// How far the evaluator's opinion of the position after a move can raise or
+/// lower it, before it has been played out
+const EVALUATION_BIAS: f64 = 0.5;
+/// How much a win loses in value for each turn it takes to come, so sooner
+/// wins are preferred
+const DISCOUNT: f64 = 0.98;
+/// A playout that goes on this many turns without a winner is cut short, and
+/// the position it reached is judged by the evaluator
+const MAX_PLAYOUT_TURNS: usize = 200;
+
+/// How a playout of the rest of the round went
+enum Playout {
+    /// The round was won, with what the win is worth and how many turns it
+    /// took
+    Won {
+        winner: usize,
+        value: f64,
+        turns_played: usize,
+    },
+    /// The round ended with nobody winning it, which counts as a loss for
+    /// everyone
+    NoWinner,
+    /// The round went on too long, and was left where it got to
+    CutShort,
+}
+
+/// A turn as it is seen across different guesses at the hidden cards: by the
+/// card played, since where it is in the player's hand changes from guess to
+/// guess
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Move {
+    card: Card,
+    field_index: usize,
+    spot: Spot,
+}
+impl Move {
+    fn of_turn(state: &GameState, turn: &Turn) -> Self {
+        Self {
+            card: state.players()[turn.player_index].hand()[turn.card_index_in_hand],
+            field_index: turn.field_index,
+            spot: turn.spot_on_field,
+        }
+    }
+}
+
+/// The position after a move by `player` in the search tree. The root has no
+/// move.
+struct Node {
+    mv: Option<Move>,
+    player: usize,
+    children: Vec<usize>,
+    visits: u32,
+    /// How many times the move could be played when its parent was visited
+    availability: u32,
+    /// How many of the playouts through the node `player` won
+    wins: f64,
+    /// A head start for moves the greedy tactics like, which fades as the
+    /// move is played out
+    bias: f64,
+}
+impl Node {
+    fn new(mv: Option<Move>, player: usize) -> Self {
+        Self {
+            mv,
+            player,
+            children: vec![],
+            visits: 0,
+            availability: 0,
+            wins: 0.0,
+            bias: 0.0,
+        }
+    }
+    /// The upper confidence bound of the node's win rate
+    fn upper_bound(&self) -> f64 {
+        self.wins / self.visits as f64
+            + EXPLORATION * ((self.availability as f64).ln() / self.visits as f64).sqrt()
+            + self.bias / (self.visits + 1) as f64
+    }
+}
+
+/// A computer player that searches for its turn with information set Monte
+/// Carlo tree search. Each playout starts from a different guess at the cards
+/// it can't see, so it never peeks at the other players' hands, and plays the
+/// round out at random. The turn played out most often is chosen.
+pub(crate) struct MctsBot {
+    rng: StdRng,
+    budget: Budget,
+    /// Steers which turns the search tries first
+    style: Style,
+    /// Weighs up the position after each turn, which also steers the search,
+    /// and judges playouts that are cut short
+    evaluator: Evaluator,
+}
+impl MctsBot {
+    pub(crate) fn new(seed: u64, budget: Budget, style: Style, evaluator: Evaluator) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            budget,
+            style,
+            evaluator,
+        }
+    }
+    /// Search for the best turn for the current player
+    fn search(&mut self, state: &GameState) -> Turn {
+        let seat = state.current_player();
+        let turns = state.legal_turns();
+        if turns.len() == 1 {
+            return turns[0];
+        }
+        // A win needs no search, with a game win taken over a round win
+        let assessments = assess_turns(state);
+        if let Some(win) = assessments
+            .iter()
+            .filter(|a| a.win.as_ref().is_some_and(|w| w.player_index == seat))
+            .max_by_key(|a| a.win.as_ref().is_some_and(|w| w.game_won))
+        {
+            return win.turn;
+        }
+        let evaluation_before = self.evaluator.evaluate(state, seat);
+        let biases: Vec<(Move, f64)> = assessments
+            .iter()
+            .map(|a| {
+                let score = greedy_score(a, state, self.style);
+                let mut after = state.clone();
+                after
+                    .apply_turn(&a.turn, &mut ())
+                    .expect("Legal turns can be played");
+                let gain = self.evaluator.evaluate(&after, seat) - evaluation_before;
+                (
+                    Move::of_turn(state, &a.turn),
+                    GREEDY_BIAS * (score / 10.0).tanh() + EVALUATION_BIAS * gain.tanh(),
+                )
+            })
+            .collect();
+
+        let mut tree = vec![Node::new(None, seat)];
+        let start = Instant::now();
+        let mut iterations = 0;
+        while match self.budget {
+            Budget::Iterations(n) => iterations < n,
+            Budget::Time(duration) => start.elapsed() < duration,
+        } {
+            self.iterate(state, &mut tree, &biases);
+            iterations += 1;
+        }
+        let most_visited = tree[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)
+            .and_then(|&child| tree[child].mv);
+        turns
+            .iter()
+            .find(|turn| Some(Move::of_turn(state, turn)) == most_visited)
+            .copied()
+            .unwrap_or(turns[0])
+    }
+    /// Guess at the hidden cards, follow the tree down to a move not tried
+    /// yet, play the round out from there, and count the result on the way
+    /// back up. The moves from the root start with the given biases.
+    fn iterate(&mut self, root: &GameState, tree: &mut Vec<Node>, biases: &[(Move, f64)]) {
+        let mut state = root.determinize(root.current_player(), &mut self.rng);
+        let round = state.round();
+        let mut node = 0;
+        let mut path = vec![0];
+        while *state.phase() == Phase::Playing && state.round() == round {
+            let mut moves: Vec<(Move, Turn)> = vec![];
+            for turn in state.legal_turns() {
+                let mv = Move::of_turn(&state, &turn);
+                if !moves.iter().any(|(m, _)| *m == mv) {
+                    moves.push((mv, turn));
+                }
+            }
+            if moves.is_empty() {
+                break;
+            }
+            let children = tree[node].children.clone();
+            let is_legal = |child: &usize| moves.iter().any(|(m, _)| tree[*child].mv == Some(*m));
+            let available: Vec<usize> = children.iter().copied().filter(is_legal).collect();
+            for &child in &available {
+                tree[child].availability += 1;
+            }
+
+            let player = state.current_player();
+            let untried: Vec<&(Move, Turn)> = moves
+                .iter()
+                .filter(|(m, _)| !children.iter().any(|&c| tree[c].mv == Some(*m)))
+                .collect();
+            if let Some(&&(mv, turn)) = untried.choose(&mut self.rng) {
+                state
+                    .apply_turn(&turn, &mut ())
+                    .expect("Legal turns can be played");
+                let mut child = Node::new(Some(mv), player);
+                child.availability = 1;
+                if node == 0 {
+                    child.bias = biases
+                        .iter()
+                        .find(|(m, _)| *m == mv)
+                        .map_or(0.0, |(_, bias)| *bias);
+                }
+                tree.push(child);
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                path.push(child);
+                break;
+            }
+
+            let child = *available
+                .iter()
+                .max_by(|&&a, &&b| tree[a].upper_bound().total_cmp(&tree[b].upper_bound()))
+                .expect("Every legal move has been tried");
+            let mv = tree[child].mv;
+            let (_, turn) = moves
+                .iter()
+                .find(|(m, _)| Some(*m) == mv)
+                .expect("The child's move is legal");
+            state
+                .apply_turn(turn, &mut ())
+                .expect("Legal turns can be played");
+            node = child;
+            path.push(child);
+        }
+
+        let result = self.play_out(&mut state, round);
+        let depth = path.len();
+        for (node_depth, node) in path.into_iter().enumerate() {
+            tree[node].visits += 1;
+            match result {
+                Playout::Won {
+                    winner,
+                    value,
+                    turns_played,
+                } if winner == tree[node].player => {
+                    // Counted from the turn the node's move was played
+                    let turns_to_win = depth - node_depth + turns_played;
+                    tree[node].wins += value * DISCOUNT.powi(turns_to_win as i32);
+                }
+                Playout::CutShort => {
+                    // Somewhere between a loss and a round win, by how the
+                    // position looks to the node's player
+                    let evaluation = self.evaluator.evaluate(&state, tree[node].player);
+                    tree[node].wins += ROUND_WIN_VALUE / (1.0 + (-evaluation).exp());
+                }
+                Playout::Won { .. } | Playout::NoWinner => {}
+            }
+        }
+    }
+    /// A turn that wins the round for the current player on their own field,
+    /// if they have one
+    fn winning_turn(&self, state: &GameState) -> Option<Turn> {
+        let player_index = state.current_player();
+        let player = &state.players()[player_index];
+        let point_threshold = state.rules().point_threshold;
+        player
+            .hand()
+            .iter()
+            .enumerate()
+            .find_map(|(card_index_in_hand, card)| {
+                winning_play(player.field(), card, point_threshold).map(|(spot, _)| Turn {
+                    player_index,
+                    field_index: player_index,
+                    card_index_in_hand,
+                    spot_on_field: spot,
+                })
+            })
+    }
+    /// Play random turns until the round is over, or has gone on too long
+    fn play_out(&mut self, state: &mut GameState, round: u32) -> Playout {
+        for turns_played in 0..MAX_PLAYOUT_TURNS {
+            match state.phase() {
+                Phase::ChoosingPrize(win_state) | Phase::GameOver(win_state) => {
+                    let value = if win_state.game_won {
+                        1.0
+                    } else {
+                        ROUND_WIN_VALUE
+                    };
+                    return Playout::Won {
+                        winner: win_state.player_index,
+                        value,
+                        turns_played,
+                    };
+                }
+                Phase::Playing if state.round() != round => return Playout::NoWinner,
+                Phase::Playing => {}
+            }
+            let turns = state.legal_turns();
+            let winning = self.winning_turn(state);
+            match winning.as_ref().or_else(|| turns.choose(&mut self.rng)) {
+                Some(turn) => state.apply_turn(turn, &mut ()).map(|_| ()),
+                None => state.pass_turn(&mut self.rng, &mut ()).map(|_| ()),
+            }
+            .expect("Legal turns and passes can be played");
+        }
+        Playout::CutShort
+    }
+}
+impl PlayerController for MctsBot {
+    fn acknowledge_turn(&mut self, _season: Season, _can_undo: bool) -> Acknowledgement {
+        Acknowledgement::Ready
+    }
+    fn choose_turn(
+        &mut self,
+        state: &GameState,
+        _player_index: usize,
+        _options: TurnOptions,
+    ) -> Action {
+        Action::Play(self.search(state))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{card::all_cards, evaluation::Weights, field::Row, rules::RuleSet};
+    use super::*;
+
+    #[test]
+    fn test_search_finds_the_game_winning_turn() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        spring.add_card_to_hand(Card::create_archer(Season::Spring, 4));
+        spring.add_card_to_hand(Card::create_countess(Season::Spring));
+        let autumn = &mut state.players_mut()[1];
+        autumn.set_deck(all_cards());
+        autumn.fill_hand(10);
+
+        // The Countess wins the whole game, with the Count in season
+        let turn = MctsBot::new(
+            0,
+            Budget::Iterations(300),
+            Style::Balanced,
+            Evaluator::default(),
+        )
+        .search(&state);
+        assert_eq!(2, turn.card_index_in_hand);
+        assert_eq!(Row::Court, *turn.spot_on_field.row());
+    }
+
+    #[test]
+    fn test_search_chooses_a_legal_turn() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let state = GameState::new_game(3, RuleSet::house(), all_cards(), &mut rng, &mut ());
+        let turn = MctsBot::new(
+            0,
+            Budget::Time(Duration::from_millis(50)),
+            Style::Balanced,
+            Evaluator::default(),
+        )
+        .search(&state);
+        assert!(state.legal_turns().contains(&turn));
+    }
+
+    #[test]
+    fn test_search_follows_the_evaluation_weights() {
+        // With no other cards in play, every playout ends the round with
+        // nobody winning it, so only the evaluator tells the turns apart
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 5));
+
+        let row_for = |court_progress: f64| {
+            let evaluator = Evaluator {
+                weights: Weights {
+                    court_progress,
+                    ..Weights::default()
+                },
+            };
+            let turn =
+                MctsBot::new(0, Budget::Iterations(200), Style::Balanced, evaluator).search(&state);
+            *turn.spot_on_field.row()
+        };
+        assert_eq!(Row::Court, row_for(10.0));
+        assert_eq!(Row::Garden, row_for(-10.0));
+    }
+}
@@ -0,0 +1,69 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use super::{
+    super::{season::Season, state::GameState},
+    Acknowledgement, Action, PlayerController, TurnOptions,
+};
+
+/// A computer player that plays any legal turn and takes any prize, all
+/// equally likely. It fills empty seats, and makes a baseline for testing
+/// other bots against.
+pub(crate) struct RandomBot {
+    rng: StdRng,
+}
+impl RandomBot {
+    /// A bot that makes the same choices whenever it is given the same seed
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+impl PlayerController for RandomBot {
+    fn acknowledge_turn(&mut self, _season: Season, _can_undo: bool) -> Acknowledgement {
+        Acknowledgement::Ready
+    }
+    fn choose_turn(
+        &mut self,
+        state: &GameState,
+        _player_index: usize,
+        _options: TurnOptions,
+    ) -> Action {
+        let turn = state
+            .legal_turns()
+            .choose(&mut self.rng)
+            .copied()
+            .expect("A player with no legal turn passes without being asked");
+        Action::Play(turn)
+    }
+    fn choose_prize(&mut self, state: &GameState, player_index: usize) -> usize {
+        state
+            .prize_choices()
+            .choose(&mut self.rng)
+            .copied()
+            .unwrap_or(player_index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{
+        card::all_cards,
+        engine::{GameEnd, GameEngine},
+        evaluation::Evaluator,
+        rules::RuleSet,
+    };
+    use super::super::Seat;
+
+    #[test]
+    fn test_random_bots_play_a_whole_game() {
+        for num_players in 2..=4 {
+            let controllers = (0..num_players)
+                .map(|seat| Seat::Random.controller(seat, Evaluator::default()))
+                .collect();
+            let mut engine = GameEngine::new(controllers, RuleSet::house(), all_cards(), 3);
+            assert!(matches!(engine.run(), GameEnd::Won(_)));
+            assert_eq!(Ok(engine.state()), engine.log().replay().as_ref());
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::{
+    super::{
+        card_tracker::CardTracker,
+        display::{self, TurnMenuChoice},
+        field::{Field, Spot},
+        score_breakdown::ScoreBreakdown,
+        season::Season,
+        state::{GameState, Pass, Phase},
+        turn::{RuleError, Turn},
+    },
+    greedy::suggest_turns,
+    Acknowledgement, Action, PlayerController, TurnOptions,
+};
+
+/// How many turns a hint suggests
+const SUGGESTIONS: usize = 3;
+
+/// A human player taking their decisions through the inquire prompts
+#[derive(Default)]
+pub(crate) struct TerminalController {
+    /// What the player can tell of where the cards are, as of the last change
+    tracker: Option<CardTracker>,
+    /// Whether to show the card tracker with the fields every turn
+    tracker_shown: bool,
+}
+impl TerminalController {
+    fn show_tracker(&self) {
+        if let Some(tracker) = &self.tracker {
+            display::show_card_tracker(tracker);
+        }
+    }
+}
+impl PlayerController for TerminalController {
+    fn acknowledge_turn(&mut self, season: Season, can_undo: bool) -> Acknowledgement {
+        if display::wait_for_next_player(season, can_undo) {
+            Acknowledgement::Ready
+        } else {
+            Acknowledgement::Undo
+        }
+    }
+    fn choose_turn(
+        &mut self,
+        state: &GameState,
+        player_index: usize,
+        options: TurnOptions,
+    ) -> Action {
+        let players = state.players();
+        let num_players = players.len();
+        let fields: Vec<&Field> = players.iter().map(|p| p.field()).collect();
+        let seasons: Vec<Season> = players.iter().map(|p| p.season()).collect();
+        let hand = players[player_index].hand();
+
+        // Show the fields starting with the current player's own
+        let seat_order: Vec<usize> = (0..num_players)
+            .map(|i| (player_index + i) % num_players)
+            .collect();
+        let fields_in_seat_order: Vec<&Field> = seat_order.iter().map(|&i| fields[i]).collect();
+        let seasons_in_seat_order: Vec<Season> = seat_order.iter().map(|&i| seasons[i]).collect();
+        display::show_round(state.round());
+        display::show_all_fields(&fields_in_seat_order, &seasons_in_seat_order);
+        if self.tracker_shown {
+            self.show_tracker();
+        }
+        let turns = state.legal_turns();
+        loop {
+            let Some(card_index_in_hand) = display::get_card_choice_from_hand(hand) else {
+                match display::get_turn_menu_choice(
+                    options.can_undo,
+                    options.can_redo,
+                    self.tracker_shown,
+                ) {
+                    TurnMenuChoice::KeepPlaying => continue,
+                    TurnMenuChoice::ExplainScores => {
+                        let breakdowns: Vec<ScoreBreakdown> =
+                            fields.iter().map(|f| ScoreBreakdown::of_field(f)).collect();
+                        display::explain_scores(
+                            &breakdowns,
+                            &seasons,
+                            state.rules().point_threshold,
+                        );
+                        continue;
+                    }
+                    TurnMenuChoice::SuggestMove => {
+                        display::show_suggestions(
+                            &suggest_turns(state, SUGGESTIONS),
+                            &seasons,
+                            player_index,
+                        );
+                        continue;
+                    }
+                    TurnMenuChoice::ShowCardTracker => {
+                        self.tracker_shown = true;
+                        self.show_tracker();
+                        continue;
+                    }
+                    TurnMenuChoice::HideCardTracker => {
+                        self.tracker_shown = false;
+                        continue;
+                    }
+                    TurnMenuChoice::Undo => return Action::Undo,
+                    TurnMenuChoice::Redo => return Action::Redo,
+                    TurnMenuChoice::SaveAndQuit => return Action::SaveAndQuit,
+                }
+            };
+            let selected_card = &hand[card_index_in_hand];
+            let mut valid_spots: Vec<Vec<Spot>> = vec![vec![]; num_players];
+            for t in turns
+                .iter()
+                .filter(|t| t.card_index_in_hand == card_index_in_hand)
+            {
+                valid_spots[t.field_index].push(t.spot_on_field);
+            }
+            if valid_spots.iter().all(|spots| spots.is_empty()) {
+                display::card_cannot_be_played(selected_card);
+                continue;
+            }
+
+            let possible_spot: Option<(usize, Spot)> = if selected_card.rune().ability().is_swap() {
+                display::select_spot_to_swap_card(
+                    selected_card,
+                    valid_spots,
+                    &fields,
+                    seasons.clone(),
+                )
+            } else {
+                display::select_spot_to_play_card(selected_card, &valid_spots[player_index])
+                    .map(|spot| (player_index, spot))
+            };
+            if let Some((field_index, spot_on_field)) = possible_spot {
+                return Action::Play(Turn {
+                    player_index,
+                    field_index,
+                    card_index_in_hand,
+                    spot_on_field,
+                });
+            }
+        }
+    }
+    fn choose_prize(&mut self, state: &GameState, player_index: usize) -> usize {
+        let players = state.players();
+        if let Phase::ChoosingPrize(win_state) = state.phase() {
+            display::round_over(players[player_index].season(), &win_state.condition);
+        }
+
+        let prizes: Vec<_> = players.iter().map(|p| p.prize()).collect();
+        let seasons: Vec<Season> = players.iter().map(|p| p.season()).collect();
+        display::choose_prize(player_index, prizes, seasons)
+    }
+    fn turn_passed(&mut self, season: Season, pass: &Pass) {
+        display::turn_passed(season, pass);
+    }
+    fn decision_rejected(&mut self, error: &RuleError) {
+        display::show_rule_error(error);
+    }
+    fn cards_tracked(&mut self, tracker: &CardTracker) {
+        self.tracker = Some(tracker.clone());
+    }
+}
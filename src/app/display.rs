@@ -1,65 +1,325 @@
-use std::str;
+use std::{str, time::Duration};
 
-use inquire::{Confirm, InquireError, Select};
+use inquire::{Confirm, InquireError, Select, Text};
+use strum_macros::Display;
 
 use super::{
-    card::{score::Score, Card},
+    card::{ability::Ability, rune::Rune, score::Score, Card},
+    card_tracker::{CardTracker, TrackedCards},
+    controller::{
+        mcts::{Budget, DEFAULT_BUDGET},
+        BotSettings, Difficulty, Seat, Style,
+    },
+    engine::UndoPolicy,
+    event::{GameEvent, ReplayStep},
     field::{Field, RowOfCards, Spot},
+    rules::{RuleSet, PRESETS},
+    score_breakdown::{CardScore, RowBreakdown, ScoreBreakdown},
     season::Season,
+    simulation::SimulationReport,
+    state::{Pass, Phase},
+    tactics::{NearWin, Reason, Suggestion},
+    turn::RuleError,
     win_condition::WinCondition,
 };
 
+/// What a player can do instead of choosing a card
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
+pub(crate) enum TurnMenuChoice {
+    #[strum(to_string = "Back to my turn")]
+    KeepPlaying,
+    #[strum(to_string = "Explain the scores")]
+    ExplainScores,
+    #[strum(to_string = "Suggest a move")]
+    SuggestMove,
+    #[strum(to_string = "Show the card tracker every turn")]
+    ShowCardTracker,
+    #[strum(to_string = "Hide the card tracker")]
+    HideCardTracker,
+    #[strum(to_string = "Undo the last turn")]
+    Undo,
+    #[strum(to_string = "Redo the undone turn")]
+    Redo,
+    #[strum(to_string = "Save the game and quit")]
+    SaveAndQuit,
+}
+
+/// The ways to move through a replay
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
+pub(crate) enum ReplayCommand {
+    Next,
+    Previous,
+    Quit,
+}
+
 /// Prompt the user for a 2, 3, or 4-player game
-pub(crate) fn get_num_players() -> i32 {
+pub(crate) fn get_num_players() -> usize {
     loop {
-        let res = Select::new("Select number of players: ", vec![2, 3, 4]).prompt();
+        let options: Vec<usize> = Season::PLAYER_COUNTS.collect();
+        let res = Select::new("Select number of players: ", options).prompt();
         if let Ok(num_players) = res {
             return num_players;
         }
     }
 }
 
-pub(crate) fn show_all_fields(fields: &[&Field]) {
-    for field in fields {
+/// Prompt for who plays each of the seasons at the table, and how the
+/// computer players play
+pub(crate) fn get_seats(seasons: &[Season]) -> Vec<Seat> {
+    seasons
+        .iter()
+        .map(|season| {
+            let settings = BotSettings {
+                difficulty: Difficulty::Normal,
+                style: Style::Balanced,
+            };
+            let options = vec![Seat::Human, Seat::Computer(settings), Seat::Random];
+            match Select::new(&format!("Who plays for {}?", season), options).prompt() {
+                Ok(Seat::Computer(_)) => Seat::Computer(get_bot_settings()),
+                Ok(seat) => seat,
+                Err(_) => Seat::Human,
+            }
+        })
+        .collect()
+}
+/// Prompt for how well a computer player plays, and what it goes for
+fn get_bot_settings() -> BotSettings {
+    let difficulties = vec![
+        Difficulty::Beginner,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Expert(DEFAULT_BUDGET),
+    ];
+    let difficulty = match Select::new("How well does it play?", difficulties)
+        .with_starting_cursor(1)
+        .prompt()
+    {
+        Ok(Difficulty::Expert(_)) => Difficulty::Expert(get_search_budget()),
+        Ok(difficulty) => difficulty,
+        Err(_) => Difficulty::Normal,
+    };
+    let styles = vec![
+        Style::Balanced,
+        Style::Aggressive,
+        Style::Builder,
+        Style::PointChaser,
+    ];
+    let style = Select::new("What does it go for?", styles)
+        .prompt()
+        .unwrap_or_default();
+    BotSettings { difficulty, style }
+}
+/// Prompt for how long a searching computer player may think on each turn
+fn get_search_budget() -> Budget {
+    let options = vec![
+        Budget::Iterations(500),
+        DEFAULT_BUDGET,
+        Budget::Time(Duration::from_secs(1)),
+        Budget::Time(Duration::from_secs(5)),
+    ];
+    Select::new("How long may it think?", options)
+        .with_starting_cursor(1)
+        .prompt()
+        .unwrap_or(DEFAULT_BUDGET)
+}
+
+/// Prompt the user for which of the built-in rule sets to play by
+pub(crate) fn get_preset_rules() -> RuleSet {
+    loop {
+        let res = Select::new("Select the rules: ", PRESETS.to_vec())
+            .with_help_message("Or put your own rules in rules.toml")
+            .prompt();
+        if let Some(rules) = res.ok().and_then(RuleSet::preset) {
+            return rules;
+        }
+    }
+}
+/// Tell the players that the game is played by the rules from a file
+pub(crate) fn rules_loaded(path: &str) {
+    println!("Playing by the rules in {}.", path);
+}
+
+/// Prompt the user for the seed of the game. Returns `None` when left blank,
+/// so that a random seed can be used instead.
+pub(crate) fn get_seed() -> Option<u64> {
+    loop {
+        let res = Text::new("Enter a game seed:")
+            .with_help_message("Leave blank for a random seed")
+            .prompt_skippable();
+        match res {
+            Ok(Some(text)) if text.trim().is_empty() => return None,
+            Ok(Some(text)) => match text.trim().parse() {
+                Ok(seed) => return Some(seed),
+                Err(_) => println!("The seed should be a whole number, found {}", text),
+            },
+            Ok(None) => return None,
+            Err(_) => {}
+        }
+    }
+}
+/// Prompt for when the players may take back their turns in this game
+pub(crate) fn get_undo_policy() -> UndoPolicy {
+    let options = vec![
+        UndoPolicy::Never,
+        UndoPolicy::BeforeNextPlayer,
+        UndoPolicy::AnyTurnInRound,
+    ];
+    Select::new("Allow undoing turns?", options)
+        .prompt()
+        .unwrap_or(UndoPolicy::Never)
+}
+/// Print the seed of the game, so it can be played again later
+pub(crate) fn show_seed(seed: u64) {
+    println!("Game seed: {}", seed);
+}
+
+/// Display the number of the round being played
+pub(crate) fn show_round(round: u32) {
+    show_title(&format!("Round {}", round));
+}
+/// Display each field under the season of the player it belongs to
+pub(crate) fn show_all_fields(fields: &[&Field], seasons: &[Season]) {
+    for (field, season) in fields.iter().zip(seasons) {
+        show_title(&season.to_string());
         show_field(field);
     }
 }
-/// Prompt the player to select a card from their hand
-pub(crate) fn get_card_choice_from_hand(hand: &[Card]) -> usize {
-    show_hand(hand);
+/// Prompt the player to select a card from their hand. Returns `None` if the
+/// player cancels the prompt.
+pub(crate) fn get_card_choice_from_hand(hand: &[Card]) -> Option<usize> {
+    show_hand("Your  Hand", hand);
     let hand_options: Vec<String> = hand.iter().map(|c| c.to_text()).collect();
     let message = "Select a card from your hand";
-    loop {
-        match Select::new(message, hand_options.clone()).raw_prompt() {
-            Ok(selected_option) => return selected_option.index,
-            Err(InquireError::OperationCanceled) => {}
-            Err(e) => panic!("{:?}", e),
-        }
+    match Select::new(message, hand_options)
+        .with_help_message("↑↓ to move, enter to select, esc for hints, scores and more")
+        .raw_prompt()
+    {
+        Ok(selected_option) => Some(selected_option.index),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => None,
+        Err(e) => panic!("{:?}", e),
     }
 }
-pub(crate) fn select_spot_to_play_card(
-    selected_card: &Card,
-    valid_spots: &Vec<Spot>,
-) -> Option<Spot> {
+/// Ask a player who cancelled their turn what they want to do instead,
+/// offering undo and redo only when they are allowed
+pub(crate) fn get_turn_menu_choice(
+    can_undo: bool,
+    can_redo: bool,
+    tracker_shown: bool,
+) -> TurnMenuChoice {
+    let options: Vec<TurnMenuChoice> = [
+        (TurnMenuChoice::KeepPlaying, true),
+        (TurnMenuChoice::ExplainScores, true),
+        (TurnMenuChoice::SuggestMove, true),
+        (TurnMenuChoice::ShowCardTracker, !tracker_shown),
+        (TurnMenuChoice::HideCardTracker, tracker_shown),
+        (TurnMenuChoice::Undo, can_undo),
+        (TurnMenuChoice::Redo, can_redo),
+        (TurnMenuChoice::SaveAndQuit, true),
+    ]
+    .into_iter()
+    .filter_map(|(choice, available)| available.then_some(choice))
+    .collect();
+    Select::new("What would you like to do?", options)
+        .prompt()
+        .unwrap_or(TurnMenuChoice::KeepPlaying)
+}
+/// Show how the points of each field were worked out, card by card, against
+/// the points needed to win
+pub(crate) fn explain_scores(
+    breakdowns: &[ScoreBreakdown],
+    seasons: &[Season],
+    point_threshold: i32,
+) {
+    for (breakdown, season) in breakdowns.iter().zip(seasons) {
+        show_title(&format!("{} Score", season));
+        explain_row(&breakdown.garden);
+        explain_row(&breakdown.court);
+        println!(
+            "{} total: {} of the {} points needed",
+            season,
+            breakdown.total(),
+            point_threshold
+        );
+    }
+}
+/// Print the suggested turns for the player at `player_index`, with what
+/// each of them does
+pub(crate) fn show_suggestions(
+    suggestions: &[Suggestion],
+    seasons: &[Season],
+    player_index: usize,
+) {
+    show_title("Suggestions");
+    for (i, suggestion) in suggestions.iter().enumerate() {
+        println!(
+            "{}. {}",
+            i + 1,
+            describe_suggestion(suggestion, seasons, player_index)
+        );
+    }
+    println!();
+}
+/// Show how many cards of each season and rune the player has seen, and how
+/// many are still hidden from them
+pub(crate) fn show_card_tracker(tracker: &CardTracker) {
+    show_title("Card Tracker");
+    for line in card_tracker_lines(tracker.counts()) {
+        println!("{}", line);
+    }
+}
+/// Ask whether to resume the saved game instead of starting a new one
+pub(crate) fn confirm_resume_saved_game() -> bool {
+    Confirm::new("Resume saved game?")
+        .with_default(true)
+        .prompt()
+        .unwrap_or(false)
+}
+/// Tell the players where the game was saved
+pub(crate) fn game_saved(path: &str) {
+    println!("Game saved to {}. Start again to resume it.", path);
+}
+/// Tell the players which round the resumed game picks up in
+pub(crate) fn game_resumed(round: u32) {
+    println!("Resuming the saved game in round {}.", round);
+}
+/// Tell the player that the card they selected has nowhere to be played
+pub(crate) fn card_cannot_be_played(selected_card: &Card) {
+    println!(
+        "Your {} cannot be played anywhere right now.",
+        selected_card.to_text()
+    );
+}
+/// Tell the players that the current player had no legal turn, and what the
+/// rules did about it
+pub(crate) fn turn_passed(season: Season, pass: &Pass) {
+    println!("{} player has no card they can play, so they pass.", season);
+    if let Some(card) = pass.discarded {
+        println!("They put their {} under their deck.", card.to_text());
+    }
+    if pass.round_ended {
+        println!("Nobody can play, so the round ends with no winner.");
+    }
+}
+pub(crate) fn select_spot_to_play_card(selected_card: &Card, valid_spots: &[Spot]) -> Option<Spot> {
     let message = format!("Select a spot to play your {}", selected_card);
 
-    match Select::new(&message, valid_spots.clone()).raw_prompt() {
+    match Select::new(&message, valid_spots.to_vec()).raw_prompt() {
         Ok(selected_spot) => Some(valid_spots[selected_spot.index]),
-        Err(InquireError::OperationCanceled) => None,
-        Err(_) => panic!(""),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => None,
+        Err(e) => panic!("{:?}", e),
     }
 }
 
 pub(crate) fn select_spot_to_swap_card(
     selected_card: &Card,
     valid_spots: Vec<Vec<Spot>>,
-    fields: &Vec<&Field>,
+    fields: &[&Field],
     seasons: Vec<Season>,
 ) -> Option<(usize, Spot)> {
     let field_message = format!("Select a field to play your {} on", selected_card);
     let spot_message = format!("Select a card to swap with your {}", selected_card);
     let available_field_indices: Vec<usize> = (0..valid_spots.len())
-        .filter(|i| valid_spots[*i].len() != 0)
+        .filter(|i| !valid_spots[*i].is_empty())
         .collect();
     let season_options: Vec<Season> = available_field_indices
         .iter()
@@ -68,9 +328,11 @@ pub(crate) fn select_spot_to_swap_card(
 
     loop {
         let field_index = match Select::new(&field_message, season_options.clone()).raw_prompt() {
-            Ok(selected_season) => selected_season.index,
-            Err(InquireError::OperationCanceled) => return None,
-            Err(_) => panic!("Encountered error"),
+            Ok(selected_season) => available_field_indices[selected_season.index],
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                return None
+            }
+            Err(e) => panic!("{:?}", e),
         };
         let options: Vec<&Card> = valid_spots[field_index]
             .iter()
@@ -83,59 +345,411 @@ pub(crate) fn select_spot_to_swap_card(
             .collect();
         let spot_index = match Select::new(&spot_message, options).raw_prompt() {
             Ok(selected_spot) => selected_spot.index,
-            Err(InquireError::OperationCanceled) => continue,
-            Err(_) => panic!("Encountered error"),
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => continue,
+            Err(e) => panic!("{:?}", e),
         };
         return Some((field_index, valid_spots[field_index][spot_index]));
     }
 }
 
-/// Prompt the winner of a round to choose from the prizes available
-pub(crate) fn choose_prize(winner: usize, prizes: Vec<&Card>, seasons: Vec<Season>) -> usize {
-    let options: Vec<String> = (0..seasons.len())
-        .map(|i| {
-            let c = prizes[i];
-            let card_description = format!(
-                "{} {} {}/{}",
-                c.season(),
-                c.rune(),
-                c.garden_score(),
-                c.court_score()
-            );
-            if i == winner {
-                format!("Your own {}", card_description)
-            } else {
-                format!("{} player's {}", seasons[i], card_description)
-            }
+/// Prompt the winner of a round to choose from the prizes available. The
+/// winner can always keep their own, even if their deck had no prize to show.
+pub(crate) fn choose_prize(
+    winner: usize,
+    prizes: Vec<&Option<Card>>,
+    seasons: Vec<Season>,
+) -> usize {
+    let describe = |c: &Card| {
+        format!(
+            "{} {} {}/{}",
+            c.season(),
+            c.rune(),
+            c.garden_score(),
+            c.court_score()
+        )
+    };
+    let (player_indices, options): (Vec<usize>, Vec<String>) = prizes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, prize)| match prize {
+            Some(c) if i == winner => Some((i, format!("Your own {}", describe(c)))),
+            Some(c) => Some((i, format!("{} player's {}", seasons[i], describe(c)))),
+            None if i == winner => Some((i, String::from("No prize (your deck had none)"))),
+            None => None,
         })
-        .collect();
+        .unzip();
 
-    Select::new("Which prize will you take?", options)
-        .raw_prompt()
-        .expect("Should make a choice.")
-        .index
+    loop {
+        match Select::new("Which prize will you take?", options.clone()).raw_prompt() {
+            Ok(selected_option) => return player_indices[selected_option.index],
+            // A prize has to be chosen before the game can be saved again
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+}
+/// Explain why a decision was not allowed
+pub(crate) fn show_rule_error(error: &RuleError) {
+    println!("That is not allowed: {}.", error);
 }
 /// Print a game over screen with the winner and winning condition
-pub(crate) fn game_over(winner_season: Season, condition: WinCondition) {
+pub(crate) fn game_over(winner_season: Season, condition: &WinCondition) {
     println!("{} player wins the game with {}!", winner_season, condition);
     println!("Play again soon!");
 }
 /// Print a round over screen with the winner and winning condition
-pub(crate) fn round_over(winner_season: Season, condition: WinCondition) {
+pub(crate) fn round_over(winner_season: Season, condition: &WinCondition) {
     println!(
         "{} player wins the round with {}!",
         winner_season, condition
     );
 }
-/// Wait for the next player to confirm that they are ready before proceeding
-pub(crate) fn wait_for_next_player(season: Season) {
-    let message = format!("{} player, press enter to start your turn.", season);
-    Confirm::new(&message)
-        .with_default(true)
+/// Ask where to save a replay of the finished game. Returns `None` if the
+/// player cancels, to skip saving it.
+pub(crate) fn get_replay_path() -> Option<String> {
+    Text::new("Save a replay of this game to:")
+        .with_default("game.calendra")
+        .with_help_message("Press Esc to skip")
+        .prompt_skippable()
+        .unwrap_or(None)
+        .filter(|path| !path.trim().is_empty())
+}
+/// Tell the players where the replay was saved, and how to watch it
+pub(crate) fn replay_saved(path: &str) {
+    println!(
+        "Replay saved to {}. Watch it with `calendra replay {}`.",
+        path, path
+    );
+}
+/// Display a step of a replay: the decision that led to it, and every
+/// player's field and hand
+pub(crate) fn show_replay_step(step_index: usize, num_steps: usize, step: &ReplayStep) {
+    let players = step.state.players();
+    let seasons: Vec<Season> = players.iter().map(|p| p.season()).collect();
+
+    show_title(&format!("Step {} of {}", step_index + 1, num_steps));
+    println!("Round {}", step.state.round());
+    match &step.decision {
+        None => println!("The cards have been dealt."),
+        Some(GameEvent::CardPlayed { turn, card }) => println!(
+            "{} player played {} on the {} field at {}.",
+            seasons[turn.player_index],
+            card.to_text(),
+            seasons[turn.field_index],
+            turn.spot_on_field
+        ),
+        Some(GameEvent::TurnUndone { player_index }) => {
+            println!("{} player took back their turn.", seasons[*player_index])
+        }
+        Some(GameEvent::TurnPassed {
+            player_index,
+            discarded,
+        }) => {
+            println!(
+                "{} player had no legal turn and passed.",
+                seasons[*player_index]
+            );
+            if let Some(card) = discarded {
+                println!("They put their {} under their deck.", card.to_text());
+            }
+        }
+        Some(GameEvent::PrizeChosen {
+            player_index,
+            prize: None,
+            ..
+        }) => println!(
+            "{} player had no prize of their own to keep.",
+            seasons[*player_index]
+        ),
+        Some(GameEvent::PrizeChosen {
+            player_index,
+            prize_index,
+            prize: Some(prize),
+        }) if player_index == prize_index => println!(
+            "{} player kept their own prize, {}.",
+            seasons[*player_index],
+            prize.to_text()
+        ),
+        Some(GameEvent::PrizeChosen {
+            player_index,
+            prize_index,
+            prize: Some(prize),
+        }) => println!(
+            "{} player took the {} player's prize, {}.",
+            seasons[*player_index],
+            seasons[*prize_index],
+            prize.to_text()
+        ),
+        Some(_) => {}
+    }
+    match step.state.phase() {
+        Phase::Playing => {}
+        Phase::ChoosingPrize(win_state) => {
+            round_over(seasons[win_state.player_index], &win_state.condition)
+        }
+        Phase::GameOver(win_state) => println!(
+            "{} player wins the game with {}!",
+            seasons[win_state.player_index], win_state.condition
+        ),
+    }
+
+    for player in players {
+        show_title(&format!("{} Field", player.season()));
+        show_field(player.field());
+        show_hand(&format!("{} Hand", player.season()), player.hand());
+    }
+}
+/// Prompt for the next move through a replay, offering only the directions
+/// that have steps left
+pub(crate) fn get_replay_command(has_previous: bool, has_next: bool) -> ReplayCommand {
+    let options: Vec<ReplayCommand> = [
+        (ReplayCommand::Next, has_next),
+        (ReplayCommand::Previous, has_previous),
+        (ReplayCommand::Quit, true),
+    ]
+    .into_iter()
+    .filter_map(|(command, available)| available.then_some(command))
+    .collect();
+    Select::new("Replay:", options)
         .prompt()
-        .expect("Cancelled");
+        .unwrap_or(ReplayCommand::Quit)
+}
+/// Wait for the next player to confirm that they are ready before proceeding.
+/// If `can_undo`, the previous player may instead take back their turn, and
+/// this returns `false`.
+pub(crate) fn wait_for_next_player(season: Season, can_undo: bool) -> bool {
+    if can_undo {
+        let start = format!("Start the {} player's turn", season);
+        let options = vec![start.clone(), String::from("Undo the last turn")];
+        let message = format!("{} player, are you ready?", season);
+        return match Select::new(&message, options).prompt() {
+            Ok(choice) => choice == start,
+            Err(_) => true,
+        };
+    }
+    let message = format!("{} player, press enter to start your turn.", season);
+    // Cancelling here only skips the confirmation; the turn itself can still be cancelled
+    let _ = Confirm::new(&message).with_default(true).prompt();
+    true
+}
+/// Print how the computer players did over a simulation
+pub(crate) fn show_simulation_report(report: &SimulationReport) {
+    let share = |count: u32| 100.0 * count as f64 / report.games as f64;
+    show_title("Simulation");
+    println!("Played {} games from seed {}.", report.games, report.seed);
+    println!();
+    println!("Wins by seat:");
+    for (i, (name, &wins)) in report.seat_names.iter().zip(&report.seat_wins).enumerate() {
+        println!(
+            "  {}. {:<20} {:>6} ({:.1}%)",
+            i + 1,
+            name,
+            wins,
+            share(wins)
+        );
+    }
+    println!("Wins by season:");
+    for (season, &wins) in report.seasons.iter().zip(&report.season_wins) {
+        println!("  {:<23} {:>6} ({:.1}%)", season, wins, share(wins));
+    }
+    println!("Games decided by:");
+    for (condition, &wins) in &report.conditions {
+        println!("  {:<23} {:>6} ({:.1}%)", condition, wins, share(wins));
+    }
+    println!(
+        "The two Ancients rule decided {} of them ({:.1}%).",
+        report.two_ancients_wins,
+        share(report.two_ancients_wins)
+    );
+    println!();
+    println!("Average rounds per game: {:.1}", report.rounds_per_game());
+    println!("Average turns per round: {:.1}", report.turns_per_round());
 }
 
+/// Describe a suggested turn and its reasons in a line, such as "Play Spring
+/// Countess 9/9 in your Court 2: completes Count+Countess in court"
+fn describe_suggestion(suggestion: &Suggestion, seasons: &[Season], player_index: usize) -> String {
+    let turn = &suggestion.turn;
+    let card = suggestion.card.to_text();
+    let play = if turn.field_index == player_index {
+        let verb = if suggestion.card.rune().ability().is_swap() {
+            "Swap"
+        } else {
+            "Play"
+        };
+        format!("{} {} into your {}", verb, card, turn.spot_on_field)
+    } else {
+        format!(
+            "Swap {} into {}'s {}",
+            card, seasons[turn.field_index], turn.spot_on_field
+        )
+    };
+    let reasons: Vec<String> = suggestion
+        .reasons
+        .iter()
+        .map(|reason| describe_reason(reason, seasons))
+        .collect();
+    if reasons.is_empty() {
+        format!("{}: nothing better stands out", play)
+    } else {
+        format!("{}: {}", play, reasons.join(", "))
+    }
+}
+/// Lay out the tracked cards with a row for each rune and a column for each
+/// season, as seen/hidden. Seasons with no cards of a rune are left blank.
+fn card_tracker_lines(counts: &[TrackedCards]) -> Vec<String> {
+    let mut seasons: Vec<Season> = vec![];
+    let mut runes: Vec<Rune> = vec![];
+    for count in counts {
+        if !seasons.contains(&count.season) {
+            seasons.push(count.season);
+        }
+        if !runes.contains(&count.rune) {
+            runes.push(count.rune);
+        }
+    }
+    runes.sort_by_key(|&rune| rune as usize);
+
+    let mut header = format!("{:<11}", "Seen/hidden");
+    for season in &seasons {
+        header += &format!("{:>8}", season.to_string());
+    }
+    let mut lines = vec![header];
+    for rune in runes {
+        let mut line = format!("{:<11}", rune.to_string());
+        for &season in &seasons {
+            let cell = counts
+                .iter()
+                .find(|c| c.season == season && c.rune == rune)
+                .map(|c| format!("{}/{}", c.seen, c.hidden))
+                .unwrap_or_default();
+            line += &format!("{:>8}", cell);
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+/// Describe what a turn does, from the point of view of the player taking it
+fn describe_reason(reason: &Reason, seasons: &[Season]) -> String {
+    let cards = |n: usize| {
+        if n == 1 {
+            String::from("1 card")
+        } else {
+            format!("{} cards", n)
+        }
+    };
+    match reason {
+        Reason::Wins {
+            condition,
+            game_won,
+            points,
+        } => {
+            let win = match condition {
+                WinCondition::CountCountess(_) => String::from("completes Count+Countess in court"),
+                WinCondition::ThreeInCourt(_) => String::from("completes three of a kind in court"),
+                WinCondition::TwoPlagues(_) => String::from("completes Two Plagues"),
+                WinCondition::FourtyPoints => format!("reaches {} points", points),
+            };
+            if *game_won {
+                format!("{} and wins the game", win)
+            } else {
+                format!("{} and wins the round", win)
+            }
+        }
+        Reason::LetsWin { player_index, win } => format!(
+            "lets {} win the round{}",
+            seasons[*player_index],
+            with_near_wins(&[*win])
+        ),
+        Reason::Breaks {
+            player_index,
+            near_wins,
+            taken,
+            threats_before,
+            threats_after,
+        } => {
+            let taking = match taken {
+                Some(card) => format!(" by taking their {}", card.to_text()),
+                None => String::new(),
+            };
+            format!(
+                "breaks up {}'s near win{}{} ({} to win it, down from {})",
+                seasons[*player_index],
+                with_near_wins(near_wins),
+                taking,
+                cards(*threats_after),
+                threats_before
+            )
+        }
+        Reason::Helps {
+            player_index,
+            threats,
+        } => format!(
+            "leaves {} {} that would win",
+            seasons[*player_index],
+            cards(*threats)
+        ),
+        Reason::SetsUp { threats, near_wins } => format!(
+            "leaves {} that would win you the round{}",
+            cards(*threats),
+            with_near_wins(near_wins)
+        ),
+        Reason::AddsPoints { points } => format!("brings your field to {} points", points),
+    }
+}
+/// Name the wins a player is near to, such as " with three Queens or Two
+/// Plagues", or nothing if there are none
+fn with_near_wins(near_wins: &[NearWin]) -> String {
+    let names: Vec<String> = near_wins
+        .iter()
+        .map(|win| match win {
+            NearWin::CountCountess => String::from("Count+Countess"),
+            NearWin::ThreeInCourt(rune) => format!("three {}s", rune),
+            NearWin::TwoPlagues => String::from("Two Plagues"),
+            NearWin::Points => String::from("points"),
+        })
+        .collect();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(" with {}", names.join(" or "))
+    }
+}
+/// Print each card's points in a row, then the row's total
+fn explain_row(row: &RowBreakdown) {
+    for card_score in &row.cards {
+        println!("  {}", explain_card_score(card_score));
+    }
+    println!("  {} total: {}", row.row, row.total());
+}
+/// Describe a card's points, from the value printed on it through each
+/// modifier applied to it
+fn explain_card_score(card_score: &CardScore) -> String {
+    let mut text = format!(
+        "{}: {} scores {}",
+        card_score.spot,
+        card_score.card.to_text(),
+        card_score.base
+    );
+    for step in &card_score.steps {
+        let modifier = if step.source.rune().ability() == Ability::AdjacentPlusOne {
+            String::from("Adj +1")
+        } else {
+            step.modifier.to_string()
+        };
+        let source = format!(
+            "{} from the {} at {}",
+            modifier,
+            step.source.rune(),
+            step.spot
+        );
+        match step.points_after {
+            Some(points) => text.push_str(&format!(", {} makes {}", source, points)),
+            None => text.push_str(&format!(", {} does not apply", source)),
+        }
+    }
+    text
+}
 /// Display a title with some fixed styling
 fn show_title(title: &str) {
     let word = format!(" {title} ");
@@ -170,48 +784,39 @@ fn show_field(field: &Field) {
     println!("+----------+----------+----------+----------+----------+");
     display_row(&field.garden, |card| card.season().to_string());
     display_row(&field.garden, |card| card.rune().to_string());
-    display_row(&field.garden, |card| display_scores(card));
+    display_row(&field.garden, display_scores);
     display_row(&field.garden, |card| card.rune().ability().to_string());
     println!("+----------+----------+----------+----------+----------+");
     display_row(&field.court, |card| card.season().to_string());
     display_row(&field.court, |card| card.rune().to_string());
-    display_row(&field.court, |card| display_scores(card));
+    display_row(&field.court, display_scores);
     display_row(&field.court, |card| card.rune().ability().to_string());
     println!("+----------+----------+----------+----------+----------+");
 }
-/// Display up to ten cards in a hand
-fn show_hand(hand: &[Card]) {
-    show_title("Your  Hand");
-    let row_vec: Vec<Option<Card>> = (0..5)
-        .map(|i| if i < hand.len() { Some(hand[i]) } else { None })
-        .collect();
-    let row = [row_vec[0], row_vec[1], row_vec[2], row_vec[3], row_vec[4]];
-
-    println!("+----------+----------+----------+----------+----------+");
-    display_row(&row, |card| card.season().to_string());
-    display_row(&row, |card| card.rune().to_string());
-    display_row(&row, |card| display_scores(card));
-    display_row(&row, |card| card.rune().ability().to_string());
+/// Display the cards in a hand under the given title, five to a row
+fn show_hand(title: &str, hand: &[Card]) {
+    show_title(title);
     println!("+----------+----------+----------+----------+----------+");
+    for cards in hand.chunks(5) {
+        let mut row: RowOfCards = [None; 5];
+        row.iter_mut()
+            .zip(cards)
+            .for_each(|(spot, card)| *spot = Some(*card));
 
-    if hand.len() <= 5 {
-        return;
+        display_row(&row, |card| card.season().to_string());
+        display_row(&row, |card| card.rune().to_string());
+        display_row(&row, display_scores);
+        display_row(&row, |card| card.rune().ability().to_string());
+        println!("+----------+----------+----------+----------+----------+");
     }
-    let row_vec: Vec<Option<Card>> = (5..10)
-        .map(|i| if i < hand.len() { Some(hand[i]) } else { None })
-        .collect();
-    let row = [row_vec[0], row_vec[1], row_vec[2], row_vec[3], row_vec[4]];
-
-    display_row(&row, |card| card.season().to_string());
-    display_row(&row, |card| card.rune().to_string());
-    display_row(&row, |card| display_scores(card));
-    display_row(&row, |card| card.rune().ability().to_string());
-    println!("+----------+----------+----------+----------+----------+");
 }
 
 #[cfg(test)]
 mod test {
-    use super::super::field::{Row, Spot};
+    use super::super::{
+        field::{Row, Spot},
+        turn::Turn,
+    };
 
     use super::*;
     #[test]
@@ -224,6 +829,97 @@ mod test {
         show_field(&field);
     }
     #[test]
+    fn test_explain_card_score() {
+        let row = [
+            Some(Card::create_beast(Season::Autumn, 8)),
+            Some(Card::create_weather(Season::Summer)),
+            None,
+            None,
+            None,
+        ];
+        let breakdown = RowBreakdown::of_row(&row, Row::Garden);
+        assert_eq!(
+            "Garden 1: Autumn Beast 8/0 scores 8, Row x2 from the Weather at Garden 2 does not apply",
+            explain_card_score(&breakdown.cards[0])
+        );
+    }
+    #[test]
+    fn test_describe_suggestion() {
+        let seasons = Season::at_table(2);
+        let suggestion = Suggestion {
+            turn: Turn {
+                player_index: 0,
+                field_index: 1,
+                card_index_in_hand: 0,
+                spot_on_field: Spot::new(Row::Court, 2),
+            },
+            card: Card::create_mist(),
+            reasons: vec![
+                Reason::Breaks {
+                    player_index: 1,
+                    near_wins: vec![NearWin::CountCountess, NearWin::ThreeInCourt(Rune::Queen)],
+                    taken: Some(Card::create_count(Season::Autumn)),
+                    threats_before: 3,
+                    threats_after: 1,
+                },
+                Reason::SetsUp {
+                    threats: 1,
+                    near_wins: vec![NearWin::TwoPlagues],
+                },
+            ],
+        };
+        assert_eq!(
+            "Swap Ferric Mist Row -1/Row -1 into Autumn's Court 3: breaks up Autumn's near win \
+             with Count+Countess or three Queens by taking their Autumn Count 8/8 \
+             (1 card to win it, down from 3), \
+             leaves 1 card that would win you the round with Two Plagues",
+            describe_suggestion(&suggestion, &seasons, 0)
+        );
+        let lets_win = Suggestion {
+            reasons: vec![Reason::LetsWin {
+                player_index: 1,
+                win: NearWin::Points,
+            }],
+            ..suggestion
+        };
+        assert_eq!(
+            "Swap Ferric Mist Row -1/Row -1 into Autumn's Court 3: \
+             lets Autumn win the round with points",
+            describe_suggestion(&lets_win, &seasons, 0)
+        );
+    }
+    #[test]
+    fn test_card_tracker_lines() {
+        let counts = [
+            TrackedCards {
+                season: Season::Spring,
+                rune: Rune::Queen,
+                seen: 1,
+                hidden: 2,
+            },
+            TrackedCards {
+                season: Season::Ferric,
+                rune: Rune::Mist,
+                seen: 0,
+                hidden: 2,
+            },
+            TrackedCards {
+                season: Season::Ferric,
+                rune: Rune::Queen,
+                seen: 3,
+                hidden: 0,
+            },
+        ];
+        assert_eq!(
+            vec![
+                "Seen/hidden  Spring  Ferric",
+                "Mist                    0/2",
+                "Queen           1/2     3/0",
+            ],
+            card_tracker_lines(&counts)
+        );
+    }
+    #[test]
     fn test_show_title() {
         show_title(&Season::Spring.to_string());
         show_title(&Season::Summer.to_string());
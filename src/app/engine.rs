@@ -0,0 +1,444 @@
+use rand::rngs::StdRng;
+use strum_macros::Display;
+
+use super::{
+    card::Card,
+    card_tracker::CardTracker,
+    controller::{Acknowledgement, Action, PlayerController, TurnOptions},
+    event::{EventSink, GameEvent, GameLog},
+    rules::RuleSet,
+    save::SavedGame,
+    state::{GameState, Outcome, Phase, WinState},
+    turn::Turn,
+};
+
+/// How a run of the game came to an end
+#[derive(PartialEq, Debug)]
+pub(crate) enum GameEnd {
+    Won(WinState),
+    /// A player stopped the game so it can be saved
+    SaveAndQuit,
+}
+
+/// When the players may take back turns they have played
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
+pub(crate) enum UndoPolicy {
+    /// Every turn is final
+    #[strum(to_string = "No undo")]
+    Never,
+    /// The last turn can be taken back until the next player is ready to start
+    #[strum(to_string = "Undo until the next player is ready")]
+    BeforeNextPlayer,
+    /// Any turn of the current round can be taken back, for practice games
+    #[strum(to_string = "Undo any turn in the round")]
+    AnyTurnInRound,
+}
+
+/// Runs a game by the rules, without any knowledge of how the players make
+/// their decisions. Every decision is delegated to the controller of the
+/// player whose turn it is.
+///
+/// All shuffling goes through a single RNG created from the game's seed, so the
+/// same seed and the same decisions always play out the same game. Every
+/// change to the state of the game is recorded to its log.
+pub(crate) struct GameEngine {
+    state: GameState,
+    controllers: Vec<Box<dyn PlayerController>>,
+    rng: StdRng,
+    /// Saved with the game, so a resumed game carries on the same log
+    log: GameLog,
+    undo_policy: UndoPolicy,
+    /// The turns of this round that can still be undone, each with the state
+    /// from before it
+    undo_history: Vec<(GameState, Turn)>,
+    /// The turns that were undone, most recent last, for redoing
+    redo_turns: Vec<Turn>,
+    /// Set after an undo, so the player whose turn it is again isn't asked to
+    /// acknowledge it a second time
+    turn_acknowledged: bool,
+    /// One for each player, each only told to that player's controller
+    trackers: Vec<CardTracker>,
+}
+impl GameEngine {
+    /// Seat one player per controller, in turn order, deal out the cards into
+    /// their decks, and start the first round of a game by the given rules
+    pub(crate) fn new(
+        controllers: Vec<Box<dyn PlayerController>>,
+        rules: RuleSet,
+        cards: Vec<Card>,
+        seed: u64,
+    ) -> Self {
+        let trackers = (0..controllers.len())
+            .map(|seat| CardTracker::new(seat, &cards))
+            .collect();
+        let mut log = GameLog::new(controllers.len(), rules, cards, seed);
+        let (state, rng) = log.start_game();
+        Self {
+            state,
+            controllers,
+            rng,
+            log,
+            undo_policy: UndoPolicy::Never,
+            undo_history: vec![],
+            redo_turns: vec![],
+            turn_acknowledged: false,
+            trackers,
+        }
+    }
+    /// Pick a saved game back up, with one controller per player. Its log is
+    /// played back to bring the RNG to where it was when the game was saved.
+    pub(crate) fn resume(
+        controllers: Vec<Box<dyn PlayerController>>,
+        saved_game: SavedGame,
+    ) -> Self {
+        assert_eq!(controllers.len(), saved_game.state.num_players());
+        let (state, rng) = saved_game
+            .log
+            .resume()
+            .expect("Saved games are checked against their log when they are loaded");
+        let trackers = (0..controllers.len())
+            .map(|seat| CardTracker::of_game(seat, &state))
+            .collect();
+        Self {
+            state,
+            controllers,
+            rng,
+            log: saved_game.log,
+            undo_policy: UndoPolicy::Never,
+            undo_history: vec![],
+            redo_turns: vec![],
+            turn_acknowledged: false,
+            trackers,
+        }
+    }
+    /// Let the players take back turns, which no turn can be by default
+    pub(crate) fn set_undo_policy(&mut self, undo_policy: UndoPolicy) {
+        self.undo_policy = undo_policy;
+    }
+    /// Snapshot the game and its log so it can be resumed later
+    pub(crate) fn save(&self) -> SavedGame {
+        SavedGame::new(self.state.clone(), self.log.clone())
+    }
+    pub(crate) fn state(&self) -> &GameState {
+        &self.state
+    }
+    /// The history of the game, from its first shuffle
+    pub(crate) fn log(&self) -> &GameLog {
+        &self.log
+    }
+    /// Ask for decisions until a player wins the game or stops it to save
+    pub(crate) fn run(&mut self) -> GameEnd {
+        loop {
+            // Each time round the loop follows a turn, pass, prize or undo
+            self.track_cards();
+            match self.state.phase() {
+                Phase::Playing => {
+                    if let Some(game_end) = self.play_turn() {
+                        return game_end;
+                    }
+                }
+                Phase::ChoosingPrize(win_state) => {
+                    let winning_player_index = win_state.player_index;
+                    self.award_prize(winning_player_index);
+                }
+                Phase::GameOver(win_state) => {
+                    debug_assert_eq!(Ok(&self.state), self.log.replay().as_ref());
+                    return GameEnd::Won(win_state.clone());
+                }
+            }
+        }
+    }
+    /// The current player chooses a card to play and a location in which to
+    /// play it, until they choose a legal turn, undo or redo a turn, or stop
+    /// the game. A player with no legal turn passes instead.
+    fn play_turn(&mut self) -> Option<GameEnd> {
+        let player_index = self.state.current_player();
+        if !std::mem::take(&mut self.turn_acknowledged) {
+            let season = self.state.players()[player_index].season();
+            let can_undo = self.undo_policy != UndoPolicy::Never && !self.undo_history.is_empty();
+            if self.controllers[player_index].acknowledge_turn(season, can_undo)
+                == Acknowledgement::Undo
+                && can_undo
+            {
+                self.undo();
+                return None;
+            }
+            if self.undo_policy == UndoPolicy::BeforeNextPlayer {
+                self.undo_history.clear();
+            }
+        }
+        if self.state.legal_turns().is_empty() {
+            let pass = self
+                .state
+                .pass_turn(&mut self.rng, &mut self.log)
+                .expect("A player with no legal turn can pass");
+            // Passing may have ended the round, so there is no going back
+            self.undo_history.clear();
+            self.redo_turns.clear();
+            let season = self.state.players()[player_index].season();
+            self.controllers[player_index].turn_passed(season, &pass);
+            return None;
+        }
+        loop {
+            let options = TurnOptions {
+                can_undo: self.undo_policy == UndoPolicy::AnyTurnInRound
+                    && !self.undo_history.is_empty(),
+                can_redo: !self.redo_turns.is_empty(),
+            };
+            let turn = match self.controllers[player_index].choose_turn(
+                &self.state,
+                player_index,
+                options,
+            ) {
+                Action::Play(turn) => turn,
+                Action::Undo if options.can_undo => {
+                    self.undo();
+                    return None;
+                }
+                Action::Redo if options.can_redo => {
+                    *self.redo_turns.last().expect("There is a turn to redo")
+                }
+                Action::Undo | Action::Redo => continue,
+                Action::SaveAndQuit => return Some(GameEnd::SaveAndQuit),
+            };
+            let state_before = self.state.clone();
+            match self.state.apply_turn(&turn, &mut self.log) {
+                Ok(Outcome::NextTurn | Outcome::Win(_)) => {
+                    if self.undo_policy != UndoPolicy::Never {
+                        self.undo_history.push((state_before, turn));
+                    }
+                    // Playing the undone turn again redoes it; any other turn
+                    // means the undone turns can't be redone any more
+                    if self.redo_turns.last() == Some(&turn) {
+                        self.redo_turns.pop();
+                    } else {
+                        self.redo_turns.clear();
+                    }
+                    return None;
+                }
+                Err(e) => self.controllers[player_index].decision_rejected(&e),
+            }
+        }
+    }
+    /// Bring each player's card tracker up to date, and tell it to their
+    /// controller
+    fn track_cards(&mut self) {
+        for (tracker, controller) in self.trackers.iter_mut().zip(&mut self.controllers) {
+            tracker.update(&self.state);
+            controller.cards_tracked(tracker);
+        }
+    }
+    /// Put the game back how it was before the last turn, so the player who
+    /// played it chooses again
+    fn undo(&mut self) {
+        let Some((state_before, turn)) = self.undo_history.pop() else {
+            return;
+        };
+        self.log.record(GameEvent::TurnUndone {
+            player_index: turn.player_index,
+        });
+        self.state = state_before;
+        self.redo_turns.push(turn);
+        self.turn_acknowledged = true;
+    }
+    /// The winner of the round chooses a prize, until they choose one that
+    /// exists. Before they do, the winning turn may be undone.
+    fn award_prize(&mut self, winning_player_index: usize) {
+        let season = self.state.players()[winning_player_index].season();
+        let can_undo = self.undo_policy != UndoPolicy::Never && !self.undo_history.is_empty();
+        if self.controllers[winning_player_index].acknowledge_turn(season, can_undo)
+            == Acknowledgement::Undo
+            && can_undo
+        {
+            self.undo();
+            return;
+        }
+        loop {
+            let prize_index = self.controllers[winning_player_index]
+                .choose_prize(&self.state, winning_player_index);
+            match self
+                .state
+                .choose_prize(prize_index, &mut self.rng, &mut self.log)
+            {
+                Ok(()) => break,
+                Err(e) => self.controllers[winning_player_index].decision_rejected(&e),
+            }
+        }
+        // The prizes have been shuffled into the decks, so there is no going back
+        self.undo_history.clear();
+        self.redo_turns.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::super::{
+        card::all_cards, controller::Seat, evaluation::Evaluator, field::Spot, season::Season,
+        turn::RuleError,
+    };
+    use super::*;
+
+    /// Takes the actions it is given in order, then falls back to the first
+    /// legal turn, and counts how many of its decisions were rejected
+    struct ScriptedController {
+        actions: Vec<Action>,
+        undo_when_acknowledging: bool,
+        rejections: Rc<Cell<usize>>,
+    }
+    impl ScriptedController {
+        fn boxed(actions: Vec<Action>) -> Box<dyn PlayerController> {
+            Box::new(Self {
+                actions,
+                undo_when_acknowledging: false,
+                rejections: Rc::new(Cell::new(0)),
+            })
+        }
+    }
+    impl PlayerController for ScriptedController {
+        fn acknowledge_turn(&mut self, _season: Season, _can_undo: bool) -> Acknowledgement {
+            if std::mem::take(&mut self.undo_when_acknowledging) {
+                Acknowledgement::Undo
+            } else {
+                Acknowledgement::Ready
+            }
+        }
+        fn choose_turn(
+            &mut self,
+            state: &GameState,
+            _player_index: usize,
+            _options: TurnOptions,
+        ) -> Action {
+            if self.actions.is_empty() {
+                Action::Play(state.legal_turns()[0])
+            } else {
+                self.actions.remove(0)
+            }
+        }
+        fn choose_prize(&mut self, _state: &GameState, player_index: usize) -> usize {
+            player_index
+        }
+        fn decision_rejected(&mut self, _error: &RuleError) {
+            self.rejections.set(self.rejections.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_illegal_turn_is_asked_for_again_without_a_terminal() {
+        let rejections = Rc::new(Cell::new(0));
+        let controllers: Vec<Box<dyn PlayerController>> = (0..2)
+            .map(|_| {
+                Box::new(ScriptedController {
+                    actions: vec![Action::Play(Turn {
+                        player_index: 0,
+                        field_index: 0,
+                        card_index_in_hand: 10,
+                        spot_on_field: Spot::from_index(0),
+                    })],
+                    undo_when_acknowledging: false,
+                    rejections: rejections.clone(),
+                }) as Box<dyn PlayerController>
+            })
+            .collect();
+        let mut engine = GameEngine::new(controllers, RuleSet::standard(), all_cards(), 3);
+        let expected_turn = engine.state().legal_turns()[0];
+
+        assert_eq!(None, engine.play_turn());
+
+        assert_eq!(1, rejections.get());
+        assert_eq!(1, engine.state().current_player());
+        assert_eq!(9, engine.state().players()[0].hand().len());
+        assert!(engine.state().players()[0]
+            .field()
+            .get(expected_turn.spot_on_field)
+            .is_some());
+    }
+
+    #[test]
+    fn test_undone_turn_is_taken_back_and_can_be_redone() {
+        let controllers = vec![
+            ScriptedController::boxed(vec![]),
+            ScriptedController::boxed(vec![]),
+        ];
+        let mut engine = GameEngine::new(controllers, RuleSet::standard(), all_cards(), 3);
+        engine.set_undo_policy(UndoPolicy::AnyTurnInRound);
+        let state_before = engine.state().clone();
+        let turn = state_before.legal_turns()[0];
+        engine.controllers = vec![
+            ScriptedController::boxed(vec![Action::Play(turn), Action::Redo]),
+            ScriptedController::boxed(vec![Action::Undo]),
+        ];
+
+        engine.play_turn();
+        let state_after = engine.state().clone();
+        engine.play_turn();
+        assert_eq!(&state_before, engine.state());
+        engine.play_turn();
+        assert_eq!(&state_after, engine.state());
+        assert!(engine.redo_turns.is_empty());
+        assert_eq!(Ok(state_after), engine.log().replay());
+    }
+
+    #[test]
+    fn test_undo_is_only_allowed_by_the_policy() {
+        let controllers = vec![
+            ScriptedController::boxed(vec![]),
+            ScriptedController::boxed(vec![]),
+        ];
+        let mut engine = GameEngine::new(controllers, RuleSet::standard(), all_cards(), 3);
+        engine.set_undo_policy(UndoPolicy::BeforeNextPlayer);
+        let state_before = engine.state().clone();
+        engine.controllers = vec![
+            ScriptedController::boxed(vec![]),
+            Box::new(ScriptedController {
+                actions: vec![Action::Undo],
+                undo_when_acknowledging: true,
+                rejections: Rc::new(Cell::new(0)),
+            }),
+        ];
+
+        // Undone before the next player is ready
+        engine.play_turn();
+        engine.play_turn();
+        assert_eq!(&state_before, engine.state());
+
+        // Once they are ready, undo is ignored and they play their turn
+        engine.play_turn();
+        engine.play_turn();
+        assert_eq!(0, engine.state().current_player());
+        assert_eq!(1, engine.undo_history.len());
+    }
+
+    #[test]
+    fn test_resumed_game_carries_on_its_log() {
+        let players = || {
+            (0..2)
+                .map(|seat| Seat::Random.controller(seat, Evaluator::default()))
+                .collect()
+        };
+        // Save once a prize has been shuffled back, so the RNG has moved on
+        let mut engine = GameEngine::new(players(), RuleSet::standard(), all_cards(), 6);
+        while engine.state().round() < 2 {
+            match engine.state().phase().clone() {
+                Phase::Playing => assert_eq!(None, engine.play_turn()),
+                Phase::ChoosingPrize(win_state) => engine.award_prize(win_state.player_index),
+                Phase::GameOver(_) => panic!("The game should last more than a round"),
+            }
+        }
+        let saved_steps = engine.log().replay_steps().unwrap();
+        let text = serde_json::to_string(&engine.save()).unwrap();
+        let mut resumed = GameEngine::resume(players(), serde_json::from_str(&text).unwrap());
+        assert_eq!(engine.state(), resumed.state());
+        assert!(matches!(resumed.run(), GameEnd::Won(_)));
+
+        // The log goes on from where it was saved, and plays back the whole game
+        let resumed_steps = resumed.log().replay_steps().unwrap();
+        assert!(resumed_steps.len() > saved_steps.len());
+        for (saved, replayed) in saved_steps.iter().zip(&resumed_steps) {
+            assert_eq!(saved.decision, replayed.decision);
+            assert_eq!(saved.state, replayed.state);
+        }
+        assert_eq!(Ok(resumed.state().clone()), resumed.log().replay());
+    }
+}
@@ -0,0 +1,226 @@
+use std::{fmt::Display, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::rune::Rune,
+    field::{Field, RowOfCards},
+    score_breakdown::ScoreBreakdown,
+    state::GameState,
+    tactics::{count_winning_cards, threat_cards},
+};
+
+/// Where the evaluation weights are looked for at start up
+pub(crate) const WEIGHTS_PATH: &str = "assets/weights.toml";
+
+/// How much each feature of a position counts towards its evaluation. The
+/// field features count for a player's own field, and against the best of
+/// the other players' fields.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Weights {
+    /// For how far the court is towards a Count/Countess or three in the
+    /// court win, from 0 for no progress to 1 for one card short
+    pub court_progress: f64,
+    /// For the field's points, as a share of the point threshold
+    pub points: f64,
+    /// For each row with a Plague and no Plague in the opposite row, where one
+    /// more Plague would win
+    pub unmatched_plagues: f64,
+    /// For each card on the field of the player's own season, which decide
+    /// whether a win ends the game
+    pub in_season_cards: f64,
+    /// For each card that could still be played this round to win another
+    /// player the round. Negative, since they count against the player.
+    pub opponent_threats: f64,
+}
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            court_progress: 3.0,
+            points: 2.0,
+            unmatched_plagues: 1.0,
+            in_season_cards: 0.25,
+            opponent_threats: -0.5,
+        }
+    }
+}
+
+/// Scores positions by a weighted sum of their features
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct Evaluator {
+    pub weights: Weights,
+}
+impl Evaluator {
+    /// How good the position is for the player at `seat`. Higher is better.
+    pub(crate) fn evaluate(&self, state: &GameState, seat: usize) -> f64 {
+        let point_threshold = state.rules().point_threshold;
+        let field_value = |player_index: usize| {
+            let player = &state.players()[player_index];
+            let field = player.field();
+            let in_season = field
+                .garden
+                .iter()
+                .chain(field.court.iter())
+                .flatten()
+                .filter(|card| card.season() == player.season())
+                .count();
+            let points = ScoreBreakdown::of_field(field).total();
+            self.weights.court_progress * court_progress(&field.court)
+                + self.weights.points * (points as f64 / point_threshold as f64).min(1.0)
+                + self.weights.unmatched_plagues * unmatched_plagues(field) as f64
+                + self.weights.in_season_cards * in_season as f64
+        };
+        let others = (0..state.num_players()).filter(|&i| i != seat);
+        let best_other = others
+            .clone()
+            .map(field_value)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let threats: usize = others
+            .map(|i| {
+                let cards = threat_cards(state, seat, i);
+                count_winning_cards(state.players()[i].field(), &cards, point_threshold)
+            })
+            .sum();
+        field_value(seat) - best_other + self.weights.opponent_threats * threats as f64
+    }
+}
+
+/// How far the court is towards its nearest court win, as a share of the
+/// cards that win needs, short of the last one
+fn court_progress(court: &RowOfCards) -> f64 {
+    let count_of = |runes: &[Rune]| {
+        court
+            .iter()
+            .flatten()
+            .filter(|card| runes.contains(&card.rune()))
+            .count()
+    };
+    let ancients = count_of(&[Rune::Ancient]);
+    let has = |rune| usize::from(count_of(&[rune]) > 0);
+    let count_countess = (has(Rune::Count) + has(Rune::Countess) + ancients).min(1) as f64;
+    let three_in_court = [Rune::Beast, Rune::Changeling, Rune::Queen]
+        .iter()
+        .map(|&rune| (count_of(&[rune]) + ancients).min(2))
+        .max()
+        .unwrap_or(0) as f64
+        / 2.0;
+    count_countess.max(three_in_court)
+}
+
+/// The rows of the field that hold a Plague, with none in the opposite row
+fn unmatched_plagues(field: &Field) -> usize {
+    let has_plague = |row: &RowOfCards| row.iter().flatten().any(|c| c.rune() == Rune::Plague);
+    usize::from(has_plague(&field.garden) != has_plague(&field.court))
+}
+
+#[derive(Debug)]
+pub(crate) enum WeightsError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Format {
+        path: String,
+        error: toml::de::Error,
+    },
+}
+impl Display for WeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightsError::Io { path, error } => write!(f, "Could not read {}: {}", path, error),
+            WeightsError::Format { path, error } => {
+                write!(f, "{} is not a valid weights file: {}", path, error)
+            }
+        }
+    }
+}
+impl std::error::Error for WeightsError {}
+
+/// Load the evaluation weights from a TOML file. Weights left out keep their
+/// default values.
+pub(crate) fn load_weights(path: &Path) -> Result<Weights, WeightsError> {
+    let path_name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|error| WeightsError::Io {
+        path: path_name.clone(),
+        error,
+    })?;
+    toml::from_str(&text).map_err(|error| WeightsError::Format {
+        path: path_name,
+        error,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{
+        card::Card,
+        field::{Row, Spot},
+        rules::RuleSet,
+        season::Season,
+    };
+    use super::*;
+
+    #[test]
+    fn test_weights_file_changes_the_defaults() {
+        let weights: Weights =
+            toml::from_str("points = 5.0\nopponent_threats = -1.0\n").expect("Should parse");
+        assert_eq!(
+            Weights {
+                points: 5.0,
+                opponent_threats: -1.0,
+                ..Weights::default()
+            },
+            weights
+        );
+        assert!(toml::from_str::<Weights>("pionts = 5.0").is_err());
+
+        let text = fs::read_to_string(WEIGHTS_PATH).expect("The weights asset should be there");
+        assert_eq!(
+            Weights::default(),
+            toml::from_str(&text).expect("Should parse")
+        );
+    }
+
+    #[test]
+    fn test_evaluation_favours_the_nearer_win() {
+        let evaluator = Evaluator::default();
+        let mut state = GameState::new(2, RuleSet::standard());
+        assert_eq!(0.0, evaluator.evaluate(&state, 0));
+
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.play_card(
+            Card::create_plague(Season::Summer),
+            Spot::new(Row::Garden, 0),
+        );
+        let spring_view = evaluator.evaluate(&state, 0);
+        assert!(spring_view > 0.0);
+        assert_eq!(1.0, court_progress(&state.players()[0].field().court));
+        assert_eq!(1, unmatched_plagues(state.players()[0].field()));
+
+        // A Countess in Spring's hand is a card away from winning, one in
+        // their deck can't be played this round
+        state.players_mut()[0].set_deck(vec![Card::create_countess(Season::Ferric)]);
+        assert_eq!(-spring_view, evaluator.evaluate(&state, 1));
+        state.players_mut()[0].add_card_to_hand(Card::create_countess(Season::Ferric));
+        assert!(evaluator.evaluate(&state, 1) < -spring_view);
+    }
+
+    #[test]
+    fn test_evaluation_counts_an_opponent_field_below_nothing() {
+        let evaluator = Evaluator::default();
+        let mut state = GameState::new(2, RuleSet::standard());
+        state.players_mut()[1].play_card(
+            Card::create_queen(Season::Spring, 3),
+            Spot::new(Row::Garden, 0),
+        );
+        state.players_mut()[1].play_card(Card::create_mist(), Spot::new(Row::Garden, 1));
+        state.players_mut()[1].play_card(Card::create_mist(), Spot::new(Row::Garden, 2));
+        state.players_mut()[1].play_card(Card::create_mist(), Spot::new(Row::Garden, 3));
+        state.players_mut()[1].play_card(Card::create_mist(), Spot::new(Row::Garden, 4));
+        assert!(ScoreBreakdown::of_field(state.players()[1].field()).total() < 0);
+        assert!(evaluator.evaluate(&state, 0) > 0.0);
+    }
+}
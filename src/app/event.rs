@@ -0,0 +1,343 @@
+use std::fmt::Display;
+
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::Card,
+    rules::RuleSet,
+    state::{GameState, WinState},
+    turn::{RuleError, Turn},
+};
+
+/// A single change to the state of a game. Together with the seed, the
+/// decisions among these (cards played, turns undone or passed, and prizes
+/// chosen) are enough to play the whole game back.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) enum GameEvent {
+    /// The shuffled cards were dealt out into the players' decks
+    DecksDealt { decks: Vec<Vec<Card>> },
+    /// A new round started with the given player to play first
+    RoundStarted { round: u32, first_player: usize },
+    /// A player's deck ran short, so their discard pile was shuffled under it
+    DiscardsReshuffled { player_index: usize },
+    /// A player drew these cards from their deck into their hand, which may be
+    /// fewer than the hand size if the deck ran short
+    HandFilled {
+        player_index: usize,
+        cards: Vec<Card>,
+    },
+    /// A player flipped the top card of their deck as their prize, or had none
+    /// to show if the deck was empty
+    PrizeRevealed {
+        player_index: usize,
+        prize: Option<Card>,
+    },
+    /// A card from the hand was played onto a field
+    CardPlayed { turn: Turn, card: Card },
+    /// The player at `player_index` had no legal turn and passed, having put
+    /// the `discarded` card under their deck if the rules say to
+    TurnPassed {
+        player_index: usize,
+        discarded: Option<Card>,
+    },
+    /// Nobody could play, so the round ended without a winner
+    RoundEndedWithoutWinner,
+    /// The last turn played, by the player at `player_index`, was taken back,
+    /// putting the game back how it was before it
+    TurnUndone { player_index: usize },
+    /// A swap sent the card it replaced to the hand of the player who played it
+    CardSwappedToHand { player_index: usize, card: Card },
+    /// A turn met a win condition, which only won the round
+    WinDetected(WinState),
+    /// The winner of the round took the prize of the player at `prize_index`,
+    /// which is `None` if they kept their own prize and had none
+    PrizeChosen {
+        player_index: usize,
+        prize_index: usize,
+        prize: Option<Card>,
+    },
+    /// A player's field was cleared at the end of a round, and the in-season
+    /// cards on it went back to their hand
+    FieldCleared {
+        player_index: usize,
+        returned_to_hand: Vec<Card>,
+    },
+    /// A turn met a win condition that won the whole game
+    GameWon(WinState),
+}
+
+/// Somewhere to send the events of a game as the rules change its state
+pub(crate) trait EventSink {
+    fn record(&mut self, event: GameEvent);
+}
+/// Throws the events away, for copies of a state that are only played on to
+/// look ahead
+impl EventSink for () {
+    fn record(&mut self, _event: GameEvent) {}
+}
+
+/// Why a log could not be played back
+#[derive(PartialEq, Debug)]
+pub(crate) enum ReplayError {
+    /// A decision in the log was not allowed by the rules
+    Rejected {
+        event_index: usize,
+        error: RuleError,
+    },
+    /// Playing the decisions back did not produce the events in the log
+    Diverged { event_index: usize },
+}
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rejected { event_index, error } => {
+                write!(
+                    f,
+                    "Event {} of the log was rejected: {}",
+                    event_index, error
+                )
+            }
+            Self::Diverged { event_index } => write!(
+                f,
+                "The replayed game differs from the log at event {}",
+                event_index
+            ),
+        }
+    }
+}
+impl std::error::Error for ReplayError {}
+
+/// The state of a replayed game after one of its decisions
+pub(crate) struct ReplayStep {
+    /// The card played, turn undone or passed, or prize chosen, or `None` at
+    /// the start of the game
+    pub decision: Option<GameEvent>,
+    pub state: GameState,
+}
+
+/// The append-only history of a game, with everything needed to play it back
+/// from the start: the players, the rules, the cards and the seed of the
+/// shuffles
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) struct GameLog {
+    num_players: usize,
+    rules: RuleSet,
+    cards: Vec<Card>,
+    seed: u64,
+    events: Vec<GameEvent>,
+}
+impl GameLog {
+    pub(crate) fn new(num_players: usize, rules: RuleSet, cards: Vec<Card>, seed: u64) -> Self {
+        Self {
+            num_players,
+            rules,
+            cards,
+            seed,
+            events: vec![],
+        }
+    }
+    /// Set up a new game from the log's seed, recording what happens to it
+    pub(crate) fn start_game(&mut self) -> (GameState, StdRng) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let state = GameState::new_game(
+            self.num_players,
+            self.rules,
+            self.cards.clone(),
+            &mut rng,
+            self,
+        );
+        (state, rng)
+    }
+    /// The seed the game was shuffled from, which the computer players'
+    /// choices are also seeded from
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// How many turns the players took, counting passes and leaving out the
+    /// turns that were taken back
+    pub(crate) fn turns_taken(&self) -> usize {
+        let count =
+            |matches: fn(&GameEvent) -> bool| self.events.iter().filter(|e| matches(e)).count();
+        count(|e| {
+            matches!(
+                e,
+                GameEvent::CardPlayed { .. } | GameEvent::TurnPassed { .. }
+            )
+        }) - count(|e| matches!(e, GameEvent::TurnUndone { .. }))
+    }
+    /// The last card played in the game, which for a finished game is the
+    /// turn that won it
+    pub(crate) fn last_turn(&self) -> Option<Turn> {
+        self.events.iter().rev().find_map(|event| match event {
+            GameEvent::CardPlayed { turn, .. } => Some(*turn),
+            _ => None,
+        })
+    }
+    /// Play the decisions in the log back from the seed, and check that they
+    /// produce exactly the same events, to rebuild the final state of the game
+    pub(crate) fn replay(&self) -> Result<GameState, ReplayError> {
+        self.resume().map(|(state, _)| state)
+    }
+    /// Play the log back like `replay`, keeping the state of the game at the
+    /// start and after each of its decisions
+    pub(crate) fn replay_steps(&self) -> Result<Vec<ReplayStep>, ReplayError> {
+        let mut steps = vec![];
+        self.play_back(|step| steps.push(step))?;
+        Ok(steps)
+    }
+    /// Play the log back like `replay`, to carry on the game from where the
+    /// log ends, with the RNG where it was then
+    pub(crate) fn resume(&self) -> Result<(GameState, StdRng), ReplayError> {
+        self.play_back(|_| {})
+    }
+    /// Play the decisions in the log back, passing on the state of the game at
+    /// the start and after each of them
+    fn play_back(
+        &self,
+        mut on_step: impl FnMut(ReplayStep),
+    ) -> Result<(GameState, StdRng), ReplayError> {
+        let mut replayed =
+            GameLog::new(self.num_players, self.rules, self.cards.clone(), self.seed);
+        let (mut state, mut rng) = replayed.start_game();
+        on_step(ReplayStep {
+            decision: None,
+            state: state.clone(),
+        });
+        // The state before each turn of the round, for the turns to be undone
+        let mut before_turns: Vec<GameState> = vec![];
+        for (event_index, event) in self.events.iter().enumerate() {
+            let result = match event {
+                GameEvent::CardPlayed { turn, .. } => {
+                    before_turns.push(state.clone());
+                    state.apply_turn(turn, &mut replayed).map(|_| ())
+                }
+                GameEvent::TurnUndone { .. } => {
+                    let Some(before_turn) = before_turns.pop() else {
+                        return Err(ReplayError::Diverged { event_index });
+                    };
+                    state = before_turn;
+                    replayed.record(event.clone());
+                    Ok(())
+                }
+                GameEvent::TurnPassed { .. } => {
+                    before_turns.clear();
+                    state.pass_turn(&mut rng, &mut replayed).map(|_| ())
+                }
+                GameEvent::PrizeChosen { prize_index, .. } => {
+                    before_turns.clear();
+                    state.choose_prize(*prize_index, &mut rng, &mut replayed)
+                }
+                _ => continue,
+            };
+            result.map_err(|error| ReplayError::Rejected { event_index, error })?;
+            on_step(ReplayStep {
+                decision: Some(event.clone()),
+                state: state.clone(),
+            });
+        }
+        if let Some(event_index) = (0..self.events.len().max(replayed.events.len()))
+            .find(|i| self.events.get(*i) != replayed.events.get(*i))
+        {
+            return Err(ReplayError::Diverged { event_index });
+        }
+        Ok((state, rng))
+    }
+}
+impl EventSink for GameLog {
+    fn record(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::seq::SliceRandom;
+
+    use super::super::{card::all_cards, rules::NoLegalTurn, state::Phase};
+    use super::*;
+
+    /// Play random legal turns, passing when there are none, with the winners
+    /// keeping their own prizes, until the game is won or the last round
+    /// given is over
+    fn play_out(log: &mut GameLog, last_round: u32) -> GameState {
+        let (mut state, mut rng) = log.start_game();
+        let mut choices = StdRng::seed_from_u64(0);
+        while state.round() <= last_round {
+            match state.phase().clone() {
+                Phase::Playing => match state.legal_turns().choose(&mut choices) {
+                    Some(turn) => {
+                        state.apply_turn(turn, log).unwrap();
+                    }
+                    None => {
+                        state.pass_turn(&mut rng, log).unwrap();
+                    }
+                },
+                Phase::ChoosingPrize(win_state) => {
+                    state
+                        .choose_prize(win_state.player_index, &mut rng, log)
+                        .unwrap();
+                }
+                Phase::GameOver(_) => break,
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn test_replaying_the_log_rebuilds_the_same_state() {
+        let mut log = GameLog::new(3, RuleSet::house(), all_cards(), 2);
+        let state = play_out(&mut log, u32::MAX);
+
+        assert!(matches!(log.events[0], GameEvent::DecksDealt { .. }));
+        assert!(log
+            .events
+            .iter()
+            .any(|e| matches!(e, GameEvent::PrizeChosen { .. })));
+        assert_eq!(Ok(state), log.replay());
+    }
+
+    #[test]
+    fn test_replaying_rebuilds_rounds_that_nobody_could_win() {
+        let rules = RuleSet {
+            hand_size: 3,
+            no_legal_turn: NoLegalTurn::DiscardToDeck,
+            ..RuleSet::standard()
+        };
+        let mut log = GameLog::new(2, rules, all_cards(), 12);
+        let state = play_out(&mut log, 5);
+
+        assert!(log.events.iter().any(|e| matches!(
+            e,
+            GameEvent::TurnPassed {
+                discarded: Some(_),
+                ..
+            }
+        )));
+        assert!(log
+            .events
+            .iter()
+            .any(|e| matches!(e, GameEvent::RoundEndedWithoutWinner)));
+        assert_eq!(Ok(state), log.replay());
+    }
+
+    #[test]
+    fn test_replay_reports_where_a_tampered_log_diverges() {
+        let mut log = GameLog::new(2, RuleSet::standard(), all_cards(), 4);
+        play_out(&mut log, u32::MAX);
+        let index = log
+            .events
+            .iter()
+            .position(|e| matches!(e, GameEvent::HandFilled { .. }))
+            .unwrap();
+        log.events[index] = GameEvent::HandFilled {
+            player_index: 0,
+            cards: vec![],
+        };
+
+        assert_eq!(
+            Err(ReplayError::Diverged { event_index: index }),
+            log.replay()
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use strum_macros::Display;
 
@@ -5,7 +6,7 @@ use super::{card::Card, season::Season};
 
 pub(crate) type RowOfCards = [Option<Card>; 5];
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) struct Field {
     pub(crate) court: RowOfCards,
     pub(crate) garden: RowOfCards,
@@ -25,19 +26,19 @@ impl Field {
     }
     pub(crate) fn set(&mut self, card: Option<Card>, spot: Spot) {
         match spot.row() {
-            Row::Court => self.court[spot.place() as usize] = card,
-            Row::Garden => self.garden[spot.place() as usize] = card,
+            Row::Court => self.court[spot.place()] = card,
+            Row::Garden => self.garden[spot.place()] = card,
         };
     }
     pub(crate) fn get(&self, spot: Spot) -> &Option<Card> {
         match spot.row() {
-            Row::Court => &self.court[spot.place() as usize],
-            Row::Garden => &self.garden[spot.place() as usize],
+            Row::Court => &self.court[spot.place()],
+            Row::Garden => &self.garden[spot.place()],
         }
     }
     /// Clone the field, keeping only the cards in the given season
     pub(crate) fn clone_in_season(&self, season: Season) -> Self {
-        let mut field_in_season = self.clone();
+        let mut field_in_season = *self;
         for row in [Row::Garden, Row::Court] {
             for place in 0..5usize {
                 let c = field_in_season.row(row)[place];
@@ -89,7 +90,9 @@ impl Row {
         }
     }
 }
-#[derive(Clone, Copy, PartialEq, Debug)]
+/// Spots are saved by their index on the field, from Garden 1 to Court 5
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "usize", into = "usize")]
 pub(crate) struct Spot {
     row: Row,
     place: usize,
@@ -106,15 +109,37 @@ impl Spot {
         &self.row
     }
     pub(crate) fn from_index(index: usize) -> Self {
+        Self::try_from_index(index)
+            .unwrap_or_else(|| panic!("Index should be less than 10, found {}", index))
+    }
+    /// The spot at the given index on the field, if there is one
+    pub(crate) fn try_from_index(index: usize) -> Option<Self> {
         if index >= 10 {
-            panic!("Index should be less than 10, found {}", index);
+            return None;
         }
-        Self {
+        Some(Self {
             row: if index < 5 { Row::Garden } else { Row::Court },
             place: index % 5,
+        })
+    }
+    pub(crate) fn index(&self) -> usize {
+        match self.row {
+            Row::Garden => self.place,
+            Row::Court => self.place + 5,
         }
     }
 }
+impl From<Spot> for usize {
+    fn from(spot: Spot) -> Self {
+        spot.index()
+    }
+}
+impl TryFrom<usize> for Spot {
+    type Error = String;
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        Spot::try_from_index(index).ok_or_else(|| format!("There is no spot {}", index))
+    }
+}
 impl Display for Spot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         format!("{} {}", self.row, self.place + 1).fmt(f)
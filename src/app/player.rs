@@ -1,4 +1,5 @@
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 use super::{
     card::Card,
@@ -9,12 +10,21 @@ use super::{
 pub(crate) type Deck = Vec<Card>;
 pub(crate) type Hand = Vec<Card>;
 
+/// Where the cards that leave play at the end of a round are put
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Pile {
+    Deck,
+    Discard,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) struct Player {
     deck: Deck,
     hand: Hand,
     prize: Option<Card>,
     field: Field,
     season: Season,
+    discard_pile: Vec<Card>,
 }
 impl Player {
     pub(crate) fn new(season: Season) -> Self {
@@ -28,10 +38,14 @@ impl Player {
             prize: None,
             field: Field::new(),
             season,
+            discard_pile: vec![],
         }
     }
 
     // Getters
+    pub(crate) fn deck(&self) -> &Deck {
+        &self.deck
+    }
     pub(crate) fn hand(&self) -> &Vec<Card> {
         &self.hand
     }
@@ -44,39 +58,65 @@ impl Player {
     pub(crate) fn season(&self) -> Season {
         self.season
     }
+    pub(crate) fn discard_pile(&self) -> &Vec<Card> {
+        &self.discard_pile
+    }
 
     // Setters
     pub(crate) fn set_deck(&mut self, deck: Vec<Card>) {
         self.deck = deck;
     }
-    pub(crate) fn take_prize(&mut self) -> Card {
-        self.prize.take().expect("Expected a prize")
+    pub(crate) fn set_hand(&mut self, hand: Vec<Card>) {
+        self.hand = hand;
+    }
+    pub(crate) fn set_discard_pile(&mut self, discard_pile: Vec<Card>) {
+        self.discard_pile = discard_pile;
+    }
+    pub(crate) fn take_prize(&mut self) -> Option<Card> {
+        self.prize.take()
     }
 
     // Actions
-    /// Draw from the deck until the hand has 10 cards
-    pub(crate) fn fill_hand(&mut self) {
-        let num_cards_to_draw = 10 - self.hand.len();
-        let at = self.deck.len() - num_cards_to_draw;
-        self.hand.append(&mut self.deck.split_off(at));
-    }
-    /// Flip the top card of the deck to show the prize
-    pub(crate) fn show_prize(&mut self) {
+    /// Draw from the deck until the hand has `hand_size` cards, or the deck
+    /// runs out, returning the cards drawn. A hand already over the size
+    /// draws nothing.
+    pub(crate) fn fill_hand(&mut self, hand_size: usize) -> Vec<Card> {
+        let num_cards_to_draw = hand_size.saturating_sub(self.hand.len());
+        let at = self.deck.len().saturating_sub(num_cards_to_draw);
+        let drawn = self.deck.split_off(at);
+        self.hand.extend_from_slice(&drawn);
+        drawn
+    }
+    /// Flip the top card of the deck to show the prize. There is no prize if
+    /// the deck is empty.
+    pub(crate) fn show_prize(&mut self) -> Option<Card> {
         self.prize = self.deck.pop();
-    }
-    /// Discard the hand into the deck (at the end of a round)
-    pub(crate) fn move_hand_to_deck(&mut self) {
-        self.deck.append(&mut self.hand);
-    }
-    /// Send all cards that are in season to the hand, all others to the deck
-    pub(crate) fn remove_cards_from_field(&mut self) {
+        self.prize
+    }
+    /// Shuffle the discard pile and put it under the deck, so the cards left in
+    /// the deck are still drawn first
+    pub(crate) fn reshuffle_discards<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.discard_pile.shuffle(rng);
+        self.discard_pile.append(&mut self.deck);
+        self.deck = std::mem::take(&mut self.discard_pile);
+    }
+    /// Put the hand away in the given pile (at the end of a round)
+    pub(crate) fn move_hand_to(&mut self, pile: Pile) {
+        let mut hand = std::mem::take(&mut self.hand);
+        self.pile_mut(pile).append(&mut hand);
+    }
+    /// Send all cards that are in season to the hand, all others to the given
+    /// pile. Returns the cards sent to the hand.
+    pub(crate) fn remove_cards_from_field(&mut self, pile: Pile) -> Vec<Card> {
+        let mut returned_to_hand = vec![];
         for i in 0..5usize {
             let opt_card = self.field.court[i].take();
             if let Some(card) = opt_card {
                 if card.season() == self.season {
+                    returned_to_hand.push(card);
                     self.hand.push(card);
                 } else {
-                    self.deck.push(card);
+                    self.pile_mut(pile).push(card);
                 }
             }
         }
@@ -84,21 +124,27 @@ impl Player {
             let opt_card = self.field.garden[i].take();
             if let Some(card) = opt_card {
                 if card.season() == self.season {
+                    returned_to_hand.push(card);
                     self.hand.push(card);
                 } else {
-                    self.deck.push(card);
+                    self.pile_mut(pile).push(card);
                 }
             }
         }
+        returned_to_hand
+    }
+    pub(crate) fn add_card_to_pile(&mut self, card: Card, pile: Pile) {
+        self.pile_mut(pile).push(card);
     }
-    pub(crate) fn add_card_to_deck(&mut self, card: Card) {
-        self.deck.push(card);
+    /// Put a card on the bottom of the deck, to be drawn last
+    pub(crate) fn put_card_under_deck(&mut self, card: Card) {
+        self.deck.insert(0, card);
     }
     pub(crate) fn add_card_to_hand(&mut self, card: Card) {
         self.hand.push(card);
     }
-    pub(crate) fn shuffle_deck(&mut self) {
-        self.deck.shuffle(&mut rand::thread_rng());
+    pub(crate) fn shuffle_deck<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.deck.shuffle(rng);
     }
     pub(crate) fn take_card_from_hand(&mut self, card_index: usize) -> Card {
         self.hand.remove(card_index)
@@ -114,9 +160,50 @@ impl Player {
         old_card
     }
     /// Set a card as the prize. (Used when swapping at the end of a round).
-    pub(crate) fn set_prize(&mut self, prize: Card) -> Option<Card> {
-        let current_prize = self.prize.take();
-        self.prize = Some(prize);
-        current_prize
+    pub(crate) fn set_prize(&mut self, prize: Option<Card>) -> Option<Card> {
+        std::mem::replace(&mut self.prize, prize)
+    }
+
+    fn pile_mut(&mut self, pile: Pile) -> &mut Vec<Card> {
+        match pile {
+            Pile::Deck => &mut self.deck,
+            Pile::Discard => &mut self.discard_pile,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_short_deck_draws_what_is_left_and_shows_no_prize() {
+        let mut player = Player::new(Season::Spring);
+        player.set_deck(vec![Card::create_queen(Season::Spring, 3); 4]);
+        assert_eq!(4, player.fill_hand(10).len());
+        assert_eq!(None, player.show_prize());
+
+        // A hand already over the size, from cards returned at the end of a
+        // round, draws nothing
+        player.set_deck(vec![Card::create_mist(); 3]);
+        player.hand.resize(12, Card::create_count(Season::Spring));
+        assert!(player.fill_hand(10).is_empty());
+        assert_eq!(3, player.deck().len());
+    }
+
+    #[test]
+    fn test_reshuffled_discards_go_under_the_deck() {
+        let mut player = Player::new(Season::Spring);
+        let left_in_deck = Card::create_queen(Season::Spring, 3);
+        player.set_deck(vec![left_in_deck]);
+        player.add_card_to_pile(Card::create_mist(), Pile::Discard);
+        player.add_card_to_pile(Card::create_count(Season::Spring), Pile::Discard);
+
+        player.reshuffle_discards(&mut StdRng::seed_from_u64(0));
+        assert!(player.discard_pile().is_empty());
+        assert_eq!(3, player.deck().len());
+        assert_eq!(vec![left_in_deck], player.fill_hand(1));
     }
 }
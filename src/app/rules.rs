@@ -0,0 +1,219 @@
+use std::{fmt::Display, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// Where a custom rule set is looked for at start up
+pub(crate) const RULES_PATH: &str = "rules.toml";
+
+/// The names of the built-in rule sets, which a rules file can start from
+pub(crate) const PRESETS: [&str; 2] = ["standard", "house"];
+
+/// What happens when a player's deck runs short at the start of a round
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DeckExhaustion {
+    /// Cards go back into the decks at the end of each round. A player draws
+    /// what is left of their deck, and has no prize that round if nothing is
+    /// left to show.
+    #[default]
+    DrawRemaining,
+    /// Cards go to a discard pile at the end of each round, which is shuffled
+    /// under the deck when it can no longer fill the hand and show a prize
+    ReshuffleDiscards,
+}
+
+/// What happens when the player whose turn it is has no legal turn, such as
+/// when their hand is empty, their field is full, or their swap cards have
+/// nothing to swap with
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum NoLegalTurn {
+    /// The player passes. Once every player has passed in a row, the round
+    /// ends with no winner.
+    #[default]
+    Pass,
+    /// The player puts the card they have held longest under their deck, then
+    /// passes
+    DiscardToDeck,
+    /// The round ends at once with no winner
+    EndRound,
+}
+
+/// The rules a game is played by, including the house rules the table has
+/// agreed on
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RuleSet {
+    /// A Count/Countess win made from two Ancients, one in season and the
+    /// other Ferric, wins the whole game
+    pub two_ancients_win_game: bool,
+    /// The points a field needs to win a round on points
+    pub point_threshold: i32,
+    /// The number of cards the players draw up to at the start of each round
+    pub hand_size: usize,
+    /// A winner who takes another player's prize of their own season puts it
+    /// straight into their hand, instead of their deck
+    pub own_season_prize_to_hand: bool,
+    /// Winning with only in-season cards wins the whole game, not just the round
+    pub in_season_win_ends_game: bool,
+    /// What happens when a deck runs short
+    #[serde(default)]
+    pub deck_exhaustion: DeckExhaustion,
+    /// What happens when a player has no legal turn
+    #[serde(default)]
+    pub no_legal_turn: NoLegalTurn,
+}
+impl RuleSet {
+    /// The rules as printed
+    pub(crate) fn standard() -> Self {
+        Self {
+            two_ancients_win_game: false,
+            point_threshold: 40,
+            hand_size: 10,
+            own_season_prize_to_hand: true,
+            in_season_win_ends_game: true,
+            deck_exhaustion: DeckExhaustion::DrawRemaining,
+            no_legal_turn: NoLegalTurn::Pass,
+        }
+    }
+    /// The standard rules with the two Ancients house rule
+    pub(crate) fn house() -> Self {
+        Self {
+            two_ancients_win_game: true,
+            ..Self::standard()
+        }
+    }
+    /// Find a built-in rule set by name
+    pub(crate) fn preset(name: &str) -> Option<Self> {
+        match name {
+            "standard" => Some(Self::standard()),
+            "house" => Some(Self::house()),
+            _ => None,
+        }
+    }
+    /// Check that a game can be played by these rules
+    pub(crate) fn check_valid(&self) -> Result<(), String> {
+        if self.hand_size == 0 {
+            return Err(String::from("The hand size must be at least 1"));
+        }
+        if self.point_threshold <= 0 {
+            return Err(format!(
+                "The point threshold must be more than 0, found {}",
+                self.point_threshold
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum RuleSetError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Format {
+        path: String,
+        error: toml::de::Error,
+    },
+    Invalid {
+        path: String,
+        message: String,
+    },
+}
+impl Display for RuleSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleSetError::Io { path, error } => write!(f, "Could not read {}: {}", path, error),
+            RuleSetError::Format { path, error } => {
+                write!(f, "{} is not a valid rules file: {}", path, error)
+            }
+            RuleSetError::Invalid { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+impl std::error::Error for RuleSetError {}
+
+/// Load a rule set from a TOML file
+pub(crate) fn load_rule_set(path: &Path) -> Result<RuleSet, RuleSetError> {
+    let path_name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|error| RuleSetError::Io {
+        path: path_name.clone(),
+        error,
+    })?;
+    parse_rule_set(&text, &path_name)
+}
+
+/// Parse the text of a rules file. It may name a `preset` to start from, which
+/// is the standard rules if left out, and then change any of its rules.
+fn parse_rule_set(text: &str, path: &str) -> Result<RuleSet, RuleSetError> {
+    let format_error = |error| RuleSetError::Format {
+        path: path.to_string(),
+        error,
+    };
+    let invalid = |message: String| RuleSetError::Invalid {
+        path: path.to_string(),
+        message,
+    };
+
+    let mut table: toml::Table = toml::from_str(text).map_err(format_error)?;
+    let preset = match table.remove("preset") {
+        None => RuleSet::standard(),
+        Some(toml::Value::String(name)) => RuleSet::preset(&name).ok_or_else(|| {
+            invalid(format!(
+                "Unknown preset {:?}, expected one of {:?}",
+                name, PRESETS
+            ))
+        })?,
+        Some(value) => return Err(invalid(format!("Expected a preset name, found {}", value))),
+    };
+    let mut rules = toml::Table::try_from(preset).expect("Rule sets are always valid TOML");
+    rules.extend(table);
+    let rules: RuleSet = rules.try_into().map_err(format_error)?;
+    rules.check_valid().map_err(invalid)?;
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rules_file_changes_its_preset() {
+        let rules = parse_rule_set(
+            "preset = \"house\"\npoint_threshold = 50\nhand_size = 8\n\
+             deck_exhaustion = \"reshuffle_discards\"\n",
+            RULES_PATH,
+        )
+        .expect("Should parse");
+        assert_eq!(
+            RuleSet {
+                point_threshold: 50,
+                hand_size: 8,
+                deck_exhaustion: DeckExhaustion::ReshuffleDiscards,
+                ..RuleSet::house()
+            },
+            rules
+        );
+        assert_eq!(
+            RuleSet::standard(),
+            parse_rule_set("", RULES_PATH).expect("Should parse")
+        );
+    }
+
+    #[test]
+    fn test_bad_rules_files_are_rejected() {
+        assert!(matches!(
+            parse_rule_set("preset = \"strict\"", RULES_PATH),
+            Err(RuleSetError::Invalid { .. })
+        ));
+        assert!(matches!(
+            parse_rule_set("hand_size = 0", RULES_PATH),
+            Err(RuleSetError::Invalid { .. })
+        ));
+        assert!(matches!(
+            parse_rule_set("point_treshold = 50", RULES_PATH),
+            Err(RuleSetError::Format { .. })
+        ));
+    }
+}
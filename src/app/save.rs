@@ -0,0 +1,306 @@
+use std::{fmt::Display, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    event::GameLog,
+    state::{GameState, Phase},
+};
+
+/// Where an interrupted game is saved, and looked for at start up
+pub(crate) const SAVE_PATH: &str = "calendra.save";
+/// The extension of replay files, which hold the whole history of a game
+pub(crate) const REPLAY_EXTENSION: &str = "calendra";
+
+/// The version of the save file format written by this build. Older versions
+/// are rejected rather than guessed at.
+const SAVE_VERSION: u32 = 1;
+/// The version of the replay file format written by this build
+const REPLAY_VERSION: u32 = 1;
+
+/// A game in progress, saved between turns
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub(crate) struct SavedGame {
+    version: u32,
+    pub(crate) state: GameState,
+    /// The game's history so far, which the resumed game carries on, so it
+    /// can still be saved as a replay. Playing it back also brings the RNG to
+    /// where it was.
+    pub(crate) log: GameLog,
+}
+impl SavedGame {
+    pub(crate) fn new(state: GameState, log: GameLog) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            state,
+            log,
+        }
+    }
+}
+
+/// A finished game, saved so it can be played back step by step
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SavedReplay {
+    version: u32,
+    log: GameLog,
+}
+
+#[derive(Debug)]
+pub(crate) enum SaveError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Format {
+        path: String,
+        error: serde_json::Error,
+    },
+    UnsupportedVersion {
+        path: String,
+        version: Option<u64>,
+        supported: u32,
+    },
+    Invalid {
+        path: String,
+        message: String,
+    },
+}
+impl Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::Io { path, error } => write!(f, "Could not access {}: {}", path, error),
+            SaveError::Format { path, error } => {
+                write!(f, "{} is not in a valid format: {}", path, error)
+            }
+            SaveError::UnsupportedVersion {
+                path,
+                version: Some(version),
+                supported,
+            } => write!(
+                f,
+                "{} was saved in format version {}, but only version {} can be loaded",
+                path, version, supported
+            ),
+            SaveError::UnsupportedVersion {
+                path,
+                version: None,
+                ..
+            } => write!(f, "{} has no format version", path),
+            SaveError::Invalid { path, message } => {
+                write!(f, "{} is not a valid game: {}", path, message)
+            }
+        }
+    }
+}
+impl std::error::Error for SaveError {}
+
+pub(crate) fn save_game(path: &Path, saved_game: &SavedGame) -> Result<(), SaveError> {
+    write_json(path, saved_game)
+}
+
+pub(crate) fn load_game(path: &Path) -> Result<SavedGame, SaveError> {
+    let path_name = path.display().to_string();
+    parse_saved_game(&read_text(path)?, &path_name)
+}
+
+pub(crate) fn save_replay(path: &Path, log: &GameLog) -> Result<(), SaveError> {
+    write_json(
+        path,
+        &SavedReplay {
+            version: REPLAY_VERSION,
+            log: log.clone(),
+        },
+    )
+}
+
+pub(crate) fn load_replay(path: &Path) -> Result<GameLog, SaveError> {
+    let path_name = path.display().to_string();
+    parse_replay(&read_text(path)?, &path_name)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), SaveError> {
+    let path_name = path.display().to_string();
+    let text = serde_json::to_string_pretty(value).map_err(|error| SaveError::Format {
+        path: path_name.clone(),
+        error,
+    })?;
+    fs::write(path, text).map_err(|error| SaveError::Io {
+        path: path_name,
+        error,
+    })
+}
+
+fn read_text(path: &Path) -> Result<String, SaveError> {
+    fs::read_to_string(path).map_err(|error| SaveError::Io {
+        path: path.display().to_string(),
+        error,
+    })
+}
+
+/// Parse the JSON, checking its version before anything else, since other
+/// versions may not have the same layout
+fn parse_versioned<T: DeserializeOwned>(
+    text: &str,
+    path: &str,
+    supported: u32,
+) -> Result<T, SaveError> {
+    let format_error = |error| SaveError::Format {
+        path: path.to_string(),
+        error,
+    };
+    let value: serde_json::Value = serde_json::from_str(text).map_err(format_error)?;
+    let version = value.get("version").and_then(|v| v.as_u64());
+    if version != Some(supported as u64) {
+        return Err(SaveError::UnsupportedVersion {
+            path: path.to_string(),
+            version,
+            supported,
+        });
+    }
+    serde_json::from_value(value).map_err(format_error)
+}
+
+fn parse_saved_game(text: &str, path: &str) -> Result<SavedGame, SaveError> {
+    let invalid = |message: String| SaveError::Invalid {
+        path: path.to_string(),
+        message,
+    };
+    let saved_game: SavedGame = parse_versioned(text, path, SAVE_VERSION)?;
+    saved_game.state.check_consistent().map_err(invalid)?;
+    if saved_game.state.phase() != &Phase::Playing {
+        return Err(invalid(String::from(
+            "Games can only be saved between turns",
+        )));
+    }
+    let replayed = saved_game
+        .log
+        .replay()
+        .map_err(|error| invalid(error.to_string()))?;
+    if replayed != saved_game.state {
+        return Err(invalid(String::from(
+            "The game does not match the log it was saved with",
+        )));
+    }
+    Ok(saved_game)
+}
+
+/// Parse a replay, and check that its decisions really do play out the game
+/// it records
+fn parse_replay(text: &str, path: &str) -> Result<GameLog, SaveError> {
+    let saved_replay: SavedReplay = parse_versioned(text, path, REPLAY_VERSION)?;
+    saved_replay
+        .log
+        .replay()
+        .map_err(|error| SaveError::Invalid {
+            path: path.to_string(),
+            message: error.to_string(),
+        })?;
+    Ok(saved_replay.log)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{card::all_cards, rules::RuleSet};
+    use super::*;
+
+    fn game_in_progress() -> SavedGame {
+        let mut log = GameLog::new(3, RuleSet::standard(), all_cards(), 11);
+        let (mut state, _) = log.start_game();
+        for _ in 0..4 {
+            let turn = state.legal_turns()[0];
+            state.apply_turn(&turn, &mut log).unwrap();
+        }
+        SavedGame::new(state, log)
+    }
+
+    #[test]
+    fn test_saved_game_loads_back_the_same() {
+        let saved_game = game_in_progress();
+        let text = serde_json::to_string(&saved_game).unwrap();
+        assert_eq!(
+            saved_game,
+            parse_saved_game(&text, SAVE_PATH).expect("Should load")
+        );
+    }
+
+    fn replay_in_progress() -> GameLog {
+        let mut log = GameLog::new(2, RuleSet::standard(), all_cards(), 8);
+        let (mut state, _) = log.start_game();
+        for _ in 0..6 {
+            let turn = state.legal_turns()[0];
+            state.apply_turn(&turn, &mut log).unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn test_replay_loads_back_the_same() {
+        let log = replay_in_progress();
+        let text = serde_json::to_string(&SavedReplay {
+            version: REPLAY_VERSION,
+            log: log.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            log,
+            parse_replay(&text, "game.calendra").expect("Should load")
+        );
+    }
+
+    #[test]
+    fn test_replay_that_does_not_play_out_is_rejected() {
+        let text = serde_json::to_string(&SavedReplay {
+            version: REPLAY_VERSION,
+            log: replay_in_progress(),
+        })
+        .unwrap()
+        .replacen("\"seed\":8", "\"seed\":9", 1);
+        assert!(matches!(
+            parse_replay(&text, "game.calendra"),
+            Err(SaveError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_other_versions_are_rejected() {
+        let mut saved_game = game_in_progress();
+        saved_game.version = SAVE_VERSION + 1;
+        let text = serde_json::to_string(&saved_game).unwrap();
+        assert!(matches!(
+            parse_saved_game(&text, SAVE_PATH),
+            Err(SaveError::UnsupportedVersion { version: Some(v), .. }) if v == u64::from(SAVE_VERSION + 1)
+        ));
+        let text = serde_json::to_string(&SavedReplay {
+            version: REPLAY_VERSION + 1,
+            log: replay_in_progress(),
+        })
+        .unwrap();
+        assert!(matches!(
+            parse_replay(&text, "game.calendra"),
+            Err(SaveError::UnsupportedVersion { version: Some(v), .. }) if v == u64::from(REPLAY_VERSION + 1)
+        ));
+    }
+
+    #[test]
+    fn test_game_that_does_not_match_its_log_is_rejected() {
+        let mut saved_game = game_in_progress();
+        let turn = saved_game.state.legal_turns()[0];
+        saved_game.state.apply_turn(&turn, &mut ()).unwrap();
+        let text = serde_json::to_string(&saved_game).unwrap();
+        assert!(matches!(
+            parse_saved_game(&text, SAVE_PATH),
+            Err(SaveError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_cards_are_rejected() {
+        let text = serde_json::to_string(&game_in_progress())
+            .unwrap()
+            .replacen("Ancient,10,10", "Ancient,11,11", 1);
+        assert!(matches!(
+            parse_saved_game(&text, SAVE_PATH),
+            Err(SaveError::Format { .. })
+        ));
+    }
+}
@@ -0,0 +1,259 @@
+use super::{
+    card::{
+        ability::Ability,
+        rune::Rune,
+        score::{RowScoreModifier, Score},
+        Card,
+    },
+    field::{Field, Row, RowOfCards, Spot},
+};
+
+/// A modifier from another card in the row, applied to a card's points
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct ModifierStep {
+    /// The card the modifier comes from, and where it is
+    pub source: Card,
+    pub spot: Spot,
+    pub modifier: RowScoreModifier,
+    /// The card's points after the modifier, or `None` if the card is exempt
+    /// from it
+    pub points_after: Option<i32>,
+}
+
+/// How the points of one card in a row were worked out
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct CardScore {
+    pub card: Card,
+    pub spot: Spot,
+    /// The value printed on the card for its row
+    pub base: i32,
+    /// Every modifier in the row, in the order they are applied
+    pub steps: Vec<ModifierStep>,
+    pub points: i32,
+}
+
+/// How the points of a row were worked out, card by card. Cards that only
+/// carry a row modifier score nothing themselves, so they have no entry.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct RowBreakdown {
+    pub row: Row,
+    pub cards: Vec<CardScore>,
+}
+impl RowBreakdown {
+    /// Work out the points of each card in a row in the following order:
+    /// 1. The value printed on the card
+    /// 2. The Adj +1 modifiers from Counts/Countesses next to it
+    /// 3. The Row +/-N modifiers, like the Mists' Row -1
+    /// 4. The Row xN modifiers, like the Weathers' Row x2 and the Plagues' Row x0
+    ///
+    /// Every modifier card applies its own modifier, so two Weathers double
+    /// the row twice.
+    pub(crate) fn of_row(cards: &RowOfCards, row: Row) -> Self {
+        let score = |card: &Card| match row {
+            Row::Garden => card.garden_score(),
+            Row::Court => card.court_score(),
+        };
+        let cards_with_spots = || {
+            cards
+                .iter()
+                .enumerate()
+                .filter_map(|(place, c)| c.map(|card| (card, Spot::new(row, place))))
+        };
+        let row_modifiers: Vec<(Card, Spot, RowScoreModifier)> = cards_with_spots()
+            .filter_map(|(card, spot)| match score(&card) {
+                Score::Mod(modifier) => Some((card, spot, modifier)),
+                Score::Value(_) => None,
+            })
+            .collect();
+        let additions = row_modifiers
+            .iter()
+            .filter(|(_, _, m)| matches!(m, RowScoreModifier::Add(_)));
+        let multipliers = row_modifiers
+            .iter()
+            .filter(|(_, _, m)| matches!(m, RowScoreModifier::Mult(_)));
+
+        let card_scores = cards_with_spots()
+            .filter_map(|(card, spot)| {
+                let Score::Value(base) = score(&card) else {
+                    return None;
+                };
+                let adjacent = cards_with_spots()
+                    .filter(|(other, other_spot)| {
+                        other.rune().ability() == Ability::AdjacentPlusOne
+                            && other_spot.place().abs_diff(spot.place()) == 1
+                    })
+                    .map(|(other, other_spot)| (other, other_spot, RowScoreModifier::Add(1)));
+
+                let mut points = base;
+                let steps = adjacent
+                    .chain(additions.clone().copied())
+                    .chain(multipliers.clone().copied())
+                    .map(|(source, source_spot, modifier)| {
+                        let points_after = if is_exempt(&card, &source) {
+                            None
+                        } else {
+                            points = match modifier {
+                                RowScoreModifier::Add(a) => points + a,
+                                RowScoreModifier::Mult(m) => points * m,
+                            };
+                            Some(points)
+                        };
+                        ModifierStep {
+                            source,
+                            spot: source_spot,
+                            modifier,
+                            points_after,
+                        }
+                    })
+                    .collect();
+                Some(CardScore {
+                    card,
+                    spot,
+                    base,
+                    steps,
+                    points,
+                })
+            })
+            .collect();
+        Self {
+            row,
+            cards: card_scores,
+        }
+    }
+    pub(crate) fn total(&self) -> i32 {
+        self.cards.iter().map(|c| c.points).sum()
+    }
+}
+
+/// How the points of a whole field were worked out
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct ScoreBreakdown {
+    pub garden: RowBreakdown,
+    pub court: RowBreakdown,
+}
+impl ScoreBreakdown {
+    pub(crate) fn of_field(field: &Field) -> Self {
+        Self {
+            garden: RowBreakdown::of_row(&field.garden, Row::Garden),
+            court: RowBreakdown::of_row(&field.court, Row::Court),
+        }
+    }
+    pub(crate) fn total(&self) -> i32 {
+        self.garden.total() + self.court.total()
+    }
+}
+
+/// Beasts ignore the Weather, and Queens, Magicians and Weathers ignore the Plague
+fn is_exempt(card: &Card, source: &Card) -> bool {
+    matches!(
+        (source.rune(), card.rune().ability()),
+        (Rune::Weather, Ability::NoWeather) | (Rune::Plague, Ability::AntiPlague)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::season::Season;
+    use super::*;
+
+    #[test]
+    fn test_modifiers_are_applied_in_order() {
+        let row = [
+            Some(Card::create_queen(Season::Spring, 3)),
+            Some(Card::create_count(Season::Spring)),
+            Some(Card::create_mist()),
+            Some(Card::create_weather(Season::Summer)),
+            Some(Card::create_beast(Season::Autumn, 8)),
+        ];
+        let breakdown = RowBreakdown::of_row(&row, Row::Court);
+
+        let queen = &breakdown.cards[0];
+        let points_after: Vec<Option<i32>> =
+            queen.steps.iter().map(|step| step.points_after).collect();
+        // Count +1, then Mist -1, then Weather x2
+        let base = queen.base;
+        assert_eq!(
+            vec![Some(base + 1), Some(base), Some(base * 2)],
+            points_after
+        );
+        assert_eq!(base * 2, queen.points);
+
+        // The Beast is not next to the Count, and ignores the Weather
+        let beast = &breakdown.cards[2];
+        assert_eq!(Rune::Beast, beast.card.rune());
+        assert_eq!(Some(&None), beast.steps.last().map(|s| &s.points_after));
+        assert_eq!(beast.base - 1, beast.points);
+
+        let count = &breakdown.cards[1];
+        assert_eq!(
+            queen.points + count.points + beast.points,
+            breakdown.total()
+        );
+    }
+
+    #[test]
+    fn test_each_weather_doubles_the_row() {
+        let row = [
+            Some(Card::create_queen(Season::Spring, 3)),
+            Some(Card::create_weather(Season::Summer)),
+            Some(Card::create_weather(Season::Winter)),
+            Some(Card::create_beast(Season::Autumn, 8)),
+            None,
+        ];
+        let breakdown = RowBreakdown::of_row(&row, Row::Garden);
+
+        let queen = &breakdown.cards[0];
+        let points_after: Vec<Option<i32>> =
+            queen.steps.iter().map(|step| step.points_after).collect();
+        assert_eq!(
+            vec![Some(queen.base * 2), Some(queen.base * 4)],
+            points_after
+        );
+        // The Beast ignores both
+        let beast = &breakdown.cards[1];
+        assert_eq!(beast.base, beast.points);
+        assert_eq!(queen.base * 4 + beast.base, breakdown.total());
+    }
+
+    #[test]
+    fn test_plague_zeroes_all_but_anti_plague_cards() {
+        let row = [
+            Some(Card::create_queen(Season::Spring, 3)),
+            Some(Card::create_beast(Season::Autumn, 8)),
+            Some(Card::create_plague(Season::Summer)),
+            None,
+            None,
+        ];
+        let breakdown = RowBreakdown::of_row(&row, Row::Garden);
+        let points: Vec<i32> = breakdown.cards.iter().map(|c| c.points).collect();
+        assert_eq!(vec![breakdown.cards[0].base, 0], points);
+    }
+
+    #[test]
+    fn test_leftmost_card_in_a_row_is_scored() {
+        // Scoring the card in the first place once looked for a Count before it
+        // by subtracting one from its place, which underflowed
+        let queen = Card::create_queen(Season::Spring, 3);
+        let count = Card::create_count(Season::Spring);
+        let points = |row: RowOfCards| -> Vec<i32> {
+            RowBreakdown::of_row(&row, Row::Court)
+                .cards
+                .iter()
+                .map(|c| c.points)
+                .collect()
+        };
+        let (Score::Value(queen_base), Score::Value(count_base)) =
+            (queen.court_score(), count.court_score())
+        else {
+            panic!("Queens and Counts score their own points");
+        };
+        assert_eq!(
+            vec![queen_base + 1, count_base],
+            points([Some(queen), Some(count), None, None, None])
+        );
+        assert_eq!(
+            vec![queen_base, count_base],
+            points([Some(queen), None, Some(count), None, None])
+        );
+    }
+}
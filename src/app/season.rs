@@ -1,6 +1,9 @@
-use strum_macros::Display;
+use std::ops::RangeInclusive;
 
-#[derive(Clone, Copy, Display, PartialEq, Debug)]
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+#[derive(Clone, Copy, Display, EnumString, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) enum Season {
     Spring,
     Summer,
@@ -8,3 +11,27 @@ pub(crate) enum Season {
     Winter,
     Ferric,
 }
+impl Season {
+    /// How many players a game can have. Player counts from outside the game
+    /// are checked against this where they come in.
+    pub(crate) const PLAYER_COUNTS: RangeInclusive<usize> = 2..=4;
+
+    /// The seasons played by the players at the table, in turn order. With fewer
+    /// than four players, the seasons left out keep their cards in the decks, where
+    /// they are out of season for everyone.
+    ///
+    /// Panics if the number of players is not in `PLAYER_COUNTS`.
+    pub(crate) fn at_table(num_players: usize) -> Vec<Season> {
+        match num_players {
+            2 => vec![Season::Spring, Season::Autumn],
+            3 => vec![Season::Spring, Season::Summer, Season::Autumn],
+            4 => vec![
+                Season::Spring,
+                Season::Summer,
+                Season::Autumn,
+                Season::Winter,
+            ],
+            _ => panic!("Expected 2, 3, or 4 players, found {}", num_players),
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    card::Card,
+    controller::{mcts::DEFAULT_BUDGET, BotSettings, Difficulty, Seat, Style},
+    engine::{GameEnd, GameEngine},
+    evaluation::Evaluator,
+    rules::{RuleSet, PRESETS},
+    season::Season,
+    win_condition::{check_two_ancients_house_rule, WinCondition},
+};
+
+/// The names of the computer players that can take part in a simulation
+pub(crate) const PLAYER_NAMES: [&str; 5] = ["random", "beginner", "normal", "greedy", "mcts"];
+/// The names of the styles a computer player can be given, after a colon
+pub(crate) const STYLE_NAMES: [&str; 4] = ["balanced", "aggressive", "builder", "points"];
+
+/// A batch of games between computer players, played without a terminal
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Simulation {
+    pub games: u32,
+    /// The computer players, by the names they were given. They move round
+    /// the table from game to game, so that each plays every season in turn.
+    pub seats: Vec<(String, Seat)>,
+    pub seed: u64,
+    /// The preset rules to play by, if one was named
+    pub rules: Option<RuleSet>,
+    /// How many games are played at once
+    pub threads: usize,
+}
+impl Simulation {
+    /// Read the simulation from command line options, such as
+    /// `--games 100 --seats greedy,random --seed 7 --rules house --threads 4`.
+    /// Only the seats are needed. A random seed is used if none is given, and
+    /// a thread for each CPU core.
+    pub(crate) fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut games = 100;
+        let mut seats = None;
+        let mut seed = None;
+        let mut rules = None;
+        let mut threads = None;
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} needs a value", option))?;
+            match option.as_str() {
+                "--games" => {
+                    games = value.parse().map_err(|_| {
+                        format!(
+                            "The number of games should be a whole number, found {}",
+                            value
+                        )
+                    })?
+                }
+                "--seats" => {
+                    seats = Some(value.split(',').map(parse_seat).collect::<Result<_, _>>()?)
+                }
+                "--seed" => {
+                    seed = Some(value.parse().map_err(|_| {
+                        format!("The seed should be a whole number, found {}", value)
+                    })?)
+                }
+                "--rules" => {
+                    rules = Some(RuleSet::preset(value).ok_or_else(|| {
+                        format!("Unknown preset {:?}, expected one of {:?}", value, PRESETS)
+                    })?)
+                }
+                "--threads" => {
+                    threads = Some(
+                        value
+                            .parse::<NonZeroUsize>()
+                            .map_err(|_| {
+                                format!(
+                                    "The number of threads should be at least 1, found {}",
+                                    value
+                                )
+                            })?
+                            .get(),
+                    )
+                }
+                _ => return Err(format!("Unknown option {}", option)),
+            }
+        }
+        let seats: Vec<(String, Seat)> =
+            seats.ok_or("Name the players with --seats, such as --seats greedy,random")?;
+        if !Season::PLAYER_COUNTS.contains(&seats.len()) {
+            return Err(format!(
+                "A game needs 2 to 4 players, found {}",
+                seats.len()
+            ));
+        }
+        if games == 0 {
+            return Err(String::from("There should be at least one game"));
+        }
+        Ok(Self {
+            games,
+            seats,
+            seed: seed.unwrap_or_else(rand::random),
+            rules,
+            threads: threads
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get)),
+        })
+    }
+    /// Play every game by the given rules, spread over the threads. Each game
+    /// is seeded from the simulation's seed and its own number, and the
+    /// threads' reports add up the same in any order, so the results are the
+    /// same however many threads play them.
+    pub(crate) fn run(
+        &self,
+        rules: RuleSet,
+        cards: &[Card],
+        evaluator: Evaluator,
+    ) -> SimulationReport {
+        let next_game = AtomicU32::new(0);
+        let play_games = || {
+            let mut report = SimulationReport::new(self);
+            loop {
+                let game = next_game.fetch_add(1, Ordering::Relaxed);
+                if game >= self.games {
+                    return report;
+                }
+                report.add(&self.play_game(game, rules, cards, evaluator));
+            }
+        };
+        let threads = self.threads.min(self.games as usize).max(1);
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads).map(|_| scope.spawn(play_games)).collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("A simulated game should not panic"))
+                .reduce(|mut total, report| {
+                    total.merge(&report);
+                    total
+                })
+                .expect("There is always a thread")
+        })
+    }
+    /// Play one game through to its end
+    fn play_game(
+        &self,
+        game: u32,
+        rules: RuleSet,
+        cards: &[Card],
+        evaluator: Evaluator,
+    ) -> GameResult {
+        let num_players = self.seats.len();
+        // Each game the seats move one place round the table
+        let rotation = game as usize % num_players;
+        let seat_at = |position: usize| (position + num_players - rotation) % num_players;
+
+        let seed = game_seed(self.seed, game);
+        let mut seeds = StdRng::seed_from_u64(seed);
+        let controllers = (0..num_players)
+            .map(|position| {
+                self.seats[seat_at(position)]
+                    .1
+                    .controller(seeds.gen(), evaluator)
+            })
+            .collect();
+        let mut engine = GameEngine::new(controllers, rules, cards.to_vec(), seed);
+        let GameEnd::Won(win_state) = engine.run() else {
+            unreachable!("Computer players never stop a game to save it");
+        };
+        let state = engine.state();
+        let winner = &state.players()[win_state.player_index];
+        // A win with only in-season cards ends the game before the two
+        // Ancients are looked at
+        let in_season = engine
+            .log()
+            .last_turn()
+            .is_some_and(|turn| state.in_season_win(&turn).is_some());
+        GameResult {
+            seat: seat_at(win_state.player_index),
+            position: win_state.player_index,
+            condition: win_state.condition,
+            two_ancients: rules.two_ancients_win_game
+                && !in_season
+                && check_two_ancients_house_rule(
+                    &winner.field().court,
+                    &win_state.condition,
+                    winner.season(),
+                ),
+            rounds: state.round(),
+            turns: engine.log().turns_taken(),
+        }
+    }
+}
+
+/// The seed of one game, from the simulation's seed and the game's number, so
+/// that no game depends on the ones played before it
+fn game_seed(seed: u64, game: u32) -> u64 {
+    let mut key = [0; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+    key[8..12].copy_from_slice(&game.to_le_bytes());
+    StdRng::from_seed(key).gen()
+}
+
+/// Read a computer player's name, with its style after a colon if it has one
+fn parse_seat(name: &str) -> Result<(String, Seat), String> {
+    let (player, style) = match name.split_once(':') {
+        Some((player, style)) => (player, Some(style)),
+        None => (name, None),
+    };
+    let style = match style {
+        None | Some("balanced") => Style::Balanced,
+        Some("aggressive") => Style::Aggressive,
+        Some("builder") => Style::Builder,
+        Some("points") => Style::PointChaser,
+        Some(style) => {
+            return Err(format!(
+                "Unknown style {:?}, expected one of {:?}",
+                style, STYLE_NAMES
+            ))
+        }
+    };
+    let difficulty = match player {
+        "random" => return Ok((name.to_string(), Seat::Random)),
+        "beginner" => Difficulty::Beginner,
+        "normal" => Difficulty::Normal,
+        "greedy" => Difficulty::Hard,
+        "mcts" => Difficulty::Expert(DEFAULT_BUDGET),
+        _ => {
+            return Err(format!(
+                "Unknown player {:?}, expected one of {:?}",
+                player, PLAYER_NAMES
+            ))
+        }
+    };
+    Ok((
+        name.to_string(),
+        Seat::Computer(BotSettings { difficulty, style }),
+    ))
+}
+
+/// How one simulated game went
+struct GameResult {
+    /// The index of the winner in the simulation's seats
+    seat: usize,
+    /// Where the winner sat at the table, which decides their season
+    position: usize,
+    condition: WinCondition,
+    /// The game was won by the two Ancients house rule
+    two_ancients: bool,
+    rounds: u32,
+    turns: usize,
+}
+
+/// How the computer players did over a simulation
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct SimulationReport {
+    pub seed: u64,
+    pub games: u32,
+    pub seat_names: Vec<String>,
+    pub seat_wins: Vec<u32>,
+    pub seasons: Vec<Season>,
+    pub season_wins: Vec<u32>,
+    /// How many games each win condition decided, by its name
+    pub conditions: BTreeMap<String, u32>,
+    /// How many games the two Ancients house rule decided
+    pub two_ancients_wins: u32,
+    pub rounds: u32,
+    pub turns: usize,
+}
+impl SimulationReport {
+    fn new(simulation: &Simulation) -> Self {
+        let num_players = simulation.seats.len();
+        Self {
+            seed: simulation.seed,
+            games: 0,
+            seat_names: simulation
+                .seats
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            seat_wins: vec![0; num_players],
+            seasons: Season::at_table(num_players),
+            season_wins: vec![0; num_players],
+            conditions: BTreeMap::new(),
+            two_ancients_wins: 0,
+            rounds: 0,
+            turns: 0,
+        }
+    }
+    fn add(&mut self, result: &GameResult) {
+        self.games += 1;
+        self.seat_wins[result.seat] += 1;
+        self.season_wins[result.position] += 1;
+        *self
+            .conditions
+            .entry(result.condition.to_string())
+            .or_default() += 1;
+        if result.two_ancients {
+            self.two_ancients_wins += 1;
+        }
+        self.rounds += result.rounds;
+        self.turns += result.turns;
+    }
+    /// Add in the games of another report of the same simulation
+    fn merge(&mut self, other: &SimulationReport) {
+        self.games += other.games;
+        for (wins, other_wins) in self.seat_wins.iter_mut().zip(&other.seat_wins) {
+            *wins += other_wins;
+        }
+        for (wins, other_wins) in self.season_wins.iter_mut().zip(&other.season_wins) {
+            *wins += other_wins;
+        }
+        for (condition, wins) in &other.conditions {
+            *self.conditions.entry(condition.clone()).or_default() += wins;
+        }
+        self.two_ancients_wins += other.two_ancients_wins;
+        self.rounds += other.rounds;
+        self.turns += other.turns;
+    }
+    pub(crate) fn rounds_per_game(&self) -> f64 {
+        self.rounds as f64 / self.games as f64
+    }
+    pub(crate) fn turns_per_round(&self) -> f64 {
+        self.turns as f64 / self.rounds as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::card::all_cards;
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_simulation_options_are_read() {
+        let simulation = Simulation::from_args(&args(
+            "--games 5 --seats greedy:builder,random --seed 3 --threads 2",
+        ))
+        .expect("Should parse");
+        assert_eq!(5, simulation.games);
+        assert_eq!(3, simulation.seed);
+        assert_eq!(None, simulation.rules);
+        assert_eq!(2, simulation.threads);
+        assert_eq!(
+            vec![
+                (
+                    String::from("greedy:builder"),
+                    Seat::Computer(BotSettings {
+                        difficulty: Difficulty::Hard,
+                        style: Style::Builder,
+                    })
+                ),
+                (String::from("random"), Seat::Random),
+            ],
+            simulation.seats
+        );
+
+        for bad in [
+            "--games 5",
+            "--seats greedy",
+            "--seats greedy,human",
+            "--seats greedy:sneaky,random",
+            "--seats greedy,random --games many",
+            "--seats greedy,random --rules strict",
+            "--seats greedy,random --seed",
+            "--seats greedy,random --threads 0",
+        ] {
+            assert!(Simulation::from_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_simulation_reports_every_game() {
+        let simulation =
+            Simulation::from_args(&args("--games 3 --seats random,random,random --seed 1"))
+                .expect("Should parse");
+        let report = simulation.run(RuleSet::house(), &all_cards(), Evaluator::default());
+        assert_eq!(3, report.games);
+        assert_eq!(3, report.seat_wins.iter().sum::<u32>());
+        assert_eq!(3, report.season_wins.iter().sum::<u32>());
+        assert_eq!(3, report.conditions.values().sum::<u32>());
+        assert!(report.rounds >= 3);
+        assert!(report.turns_per_round() > 1.0);
+
+        // The same seed plays the same games, on any number of threads
+        let one_thread = Simulation {
+            threads: 1,
+            ..simulation
+        };
+        assert_eq!(
+            report,
+            one_thread.run(RuleSet::house(), &all_cards(), Evaluator::default())
+        );
+    }
+}
@@ -0,0 +1,935 @@
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::Card,
+    event::{EventSink, GameEvent},
+    player::{Pile, Player},
+    rules::{DeckExhaustion, NoLegalTurn, RuleSet},
+    season::Season,
+    turn::{self, RuleError, Turn},
+    win_condition::{check_two_ancients_house_rule, check_win, WinCondition},
+};
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) struct WinState {
+    pub player_index: usize,
+    pub game_won: bool,
+    pub condition: WinCondition,
+}
+
+/// What happened as a result of a legal turn
+#[derive(PartialEq, Debug)]
+pub(crate) enum Outcome {
+    /// Play passes on to the next player
+    NextTurn,
+    /// The turn won the round, or the whole game
+    Win(WinState),
+}
+
+/// What happened when a player with no legal turn passed
+#[derive(PartialEq, Debug)]
+pub(crate) struct Pass {
+    /// The card put under the deck, if the rules say to discard one
+    pub discarded: Option<Card>,
+    /// Nobody could play, so the round ended with no winner
+    pub round_ended: bool,
+}
+
+/// The decision the game is waiting for
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) enum Phase {
+    /// The current player must play a card
+    Playing,
+    /// The winner of the round must choose a prize
+    ChoosingPrize(WinState),
+    /// The game has been won, and there is nothing left to decide
+    GameOver(WinState),
+}
+
+/// A complete snapshot of a game: every player's zones, the round, whose turn
+/// it is, and what decision comes next. All the rules are applied here, so a
+/// copy can be cloned off to try out moves and then thrown away. Each change
+/// the rules make is reported to the `EventSink` passed in.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) struct GameState {
+    players: Vec<Player>,
+    round: u32,
+    first_player: usize,
+    current_player: usize,
+    phase: Phase,
+    rules: RuleSet,
+    /// How many players in a row have passed for having no legal turn
+    passes_in_a_row: usize,
+}
+impl GameState {
+    /// Seat the players of the given number at the table, with empty decks, to
+    /// play by the given rules
+    pub(crate) fn new(num_players: usize, rules: RuleSet) -> Self {
+        Self {
+            players: Season::at_table(num_players)
+                .into_iter()
+                .map(Player::new)
+                .collect(),
+            round: 0,
+            first_player: 0,
+            current_player: 0,
+            phase: Phase::Playing,
+            rules,
+            passes_in_a_row: 0,
+        }
+    }
+
+    /// Seat the players, deal out the cards into their decks, and start the
+    /// first round
+    pub(crate) fn new_game<R: Rng + ?Sized>(
+        num_players: usize,
+        rules: RuleSet,
+        cards: Vec<Card>,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) -> Self {
+        let mut state = Self::new(num_players, rules);
+        state.deal(cards, rng, events);
+        state.start_round(rng, events);
+        state
+    }
+
+    // Getters
+    pub(crate) fn players(&self) -> &[Player] {
+        &self.players
+    }
+    pub(crate) fn num_players(&self) -> usize {
+        self.players.len()
+    }
+    pub(crate) fn round(&self) -> u32 {
+        self.round
+    }
+    pub(crate) fn current_player(&self) -> usize {
+        self.current_player
+    }
+    pub(crate) fn phase(&self) -> &Phase {
+        &self.phase
+    }
+    pub(crate) fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+    /// Every card the player at `seat` can see: the cards on all of the
+    /// fields, their own hand, and the prizes
+    pub(crate) fn seen_cards(&self, seat: usize) -> Vec<Card> {
+        let on_fields = self.players.iter().flat_map(|p| {
+            let field = p.field();
+            field.garden.iter().chain(field.court.iter()).flatten()
+        });
+        let prizes = self.players.iter().filter_map(|p| p.prize().as_ref());
+        on_fields
+            .chain(self.players[seat].hand())
+            .chain(prizes)
+            .copied()
+            .collect()
+    }
+    /// Every card the player at `seat` cannot see: the other players' hands,
+    /// and the cards in all of the decks and discard piles
+    pub(crate) fn unseen_cards(&self, seat: usize) -> Vec<Card> {
+        self.players
+            .iter()
+            .enumerate()
+            .flat_map(|(i, p)| {
+                let hand: &[Card] = if i == seat { &[] } else { p.hand() };
+                hand.iter().chain(p.deck()).chain(p.discard_pile())
+            })
+            .copied()
+            .collect()
+    }
+    /// A copy of the state as the player at `seat` might imagine it: the cards
+    /// they can't see are shuffled and dealt back out to where they were, so
+    /// every hidden hand, deck and discard pile keeps its size
+    pub(crate) fn determinize<R: Rng + ?Sized>(&self, seat: usize, rng: &mut R) -> Self {
+        let mut hidden = self.unseen_cards(seat);
+        hidden.shuffle(rng);
+        let mut deal = |n: usize| hidden.split_off(hidden.len() - n);
+        let mut state = self.clone();
+        for (i, player) in state.players.iter_mut().enumerate() {
+            if i != seat {
+                let hand = deal(player.hand().len());
+                player.set_hand(hand);
+            }
+            let deck = deal(player.deck().len());
+            player.set_deck(deck);
+            let discard_pile = deal(player.discard_pile().len());
+            player.set_discard_pile(discard_pile);
+        }
+        state
+    }
+    /// Direct access to the players, for setting up positions in tests
+    #[cfg(test)]
+    pub(crate) fn players_mut(&mut self) -> &mut [Player] {
+        &mut self.players
+    }
+
+    /// Check that a state read from outside the game, such as a save file, has
+    /// seats and indices the rules can work with
+    pub(crate) fn check_consistent(&self) -> Result<(), String> {
+        let num_players = self.num_players();
+        if !Season::PLAYER_COUNTS.contains(&num_players) {
+            return Err(format!(
+                "Expected 2, 3, or 4 players, found {}",
+                num_players
+            ));
+        }
+        let seasons: Vec<Season> = self.players.iter().map(|p| p.season()).collect();
+        if seasons != Season::at_table(num_players) {
+            return Err(format!(
+                "Expected the seasons {:?} at the table, found {:?}",
+                Season::at_table(num_players),
+                seasons
+            ));
+        }
+        self.rules.check_valid()?;
+        if self.first_player >= num_players || self.current_player >= num_players {
+            return Err(String::from(
+                "The first and current players must be at the table",
+            ));
+        }
+        Ok(())
+    }
+
+    // Actions
+    /// Shuffle all the cards together, and deal them out to the players'
+    /// decks one at a time, so each deck gets an even share
+    pub(crate) fn deal<R: Rng + ?Sized>(
+        &mut self,
+        mut all_cards: Vec<Card>,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) {
+        all_cards.shuffle(rng);
+
+        let num_players = self.num_players();
+        let mut decks: Vec<Vec<Card>> = vec![vec![]; num_players];
+        for (i, card) in all_cards.into_iter().enumerate() {
+            decks[i % num_players].push(card);
+        }
+        events.record(GameEvent::DecksDealt {
+            decks: decks.clone(),
+        });
+        self.players
+            .iter_mut()
+            .zip(decks)
+            .for_each(|(p, d)| p.set_deck(d));
+    }
+    /// Start the next round: players draw their hands up to the hand size and
+    /// flip the top card of their decks to show their prize. A deck that runs
+    /// short is handled by the deck exhaustion rule.
+    pub(crate) fn start_round<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) {
+        self.round += 1;
+        self.current_player = self.first_player;
+        self.phase = Phase::Playing;
+        self.passes_in_a_row = 0;
+        events.record(GameEvent::RoundStarted {
+            round: self.round,
+            first_player: self.first_player,
+        });
+        for (player_index, player) in self.players.iter_mut().enumerate() {
+            let cards_needed = self.rules.hand_size.saturating_sub(player.hand().len()) + 1;
+            if self.rules.deck_exhaustion == DeckExhaustion::ReshuffleDiscards
+                && player.deck().len() < cards_needed
+                && !player.discard_pile().is_empty()
+            {
+                player.reshuffle_discards(rng);
+                events.record(GameEvent::DiscardsReshuffled { player_index });
+            }
+            let cards = player.fill_hand(self.rules.hand_size);
+            events.record(GameEvent::HandFilled {
+                player_index,
+                cards,
+            });
+            let prize = player.show_prize();
+            events.record(GameEvent::PrizeRevealed {
+                player_index,
+                prize,
+            });
+        }
+    }
+    /// Every turn the current player could take
+    pub(crate) fn legal_turns(&self) -> Vec<Turn> {
+        turn::legal_turns(self, self.current_player)
+    }
+    /// The players whose prize the winner of the round may take: each player
+    /// showing a prize, and the winner themself, even without one
+    pub(crate) fn prize_choices(&self) -> Vec<usize> {
+        let Phase::ChoosingPrize(win_state) = &self.phase else {
+            return vec![];
+        };
+        (0..self.num_players())
+            .filter(|&i| i == win_state.player_index || self.players[i].prize().is_some())
+            .collect()
+    }
+    /// Check that the turn follows the rules and play it, then check whether
+    /// it won. An illegal turn is rejected before anything changes.
+    pub(crate) fn apply_turn(
+        &mut self,
+        turn: &Turn,
+        events: &mut impl EventSink,
+    ) -> Result<Outcome, RuleError> {
+        if self.phase != Phase::Playing {
+            return Err(RuleError::RoundNotInProgress);
+        }
+        if turn.player_index != self.current_player {
+            return Err(RuleError::NotPlayersTurn {
+                expected: self.current_player,
+                found: turn.player_index,
+            });
+        }
+        turn.check_legal(&self.players)?;
+        self.execute_turn(turn, events);
+        self.passes_in_a_row = 0;
+
+        if let Some(win_state) = self.check_for_win_conditions(turn) {
+            if win_state.game_won {
+                events.record(GameEvent::GameWon(win_state.clone()));
+                self.phase = Phase::GameOver(win_state.clone());
+            } else {
+                events.record(GameEvent::WinDetected(win_state.clone()));
+                self.phase = Phase::ChoosingPrize(win_state.clone());
+            }
+            return Ok(Outcome::Win(win_state));
+        }
+        self.current_player = (self.current_player + 1) % self.num_players();
+        Ok(Outcome::NextTurn)
+    }
+    /// After a round is over, the winner takes the prize of the player at
+    /// `prize_index`, cards in the hands and fields are put away, and the next
+    /// round begins with the winner playing first. The winner may always keep
+    /// their own prize, even when their deck had none to show.
+    pub(crate) fn choose_prize<R: Rng + ?Sized>(
+        &mut self,
+        prize_index: usize,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) -> Result<(), RuleError> {
+        let Phase::ChoosingPrize(win_state) = &self.phase else {
+            return Err(RuleError::NoPrizeToChoose);
+        };
+        let winning_player_index = win_state.player_index;
+        if !self.prize_choices().contains(&prize_index) {
+            return Err(RuleError::NoSuchPrize(prize_index));
+        }
+        events.record(GameEvent::PrizeChosen {
+            player_index: winning_player_index,
+            prize_index,
+            prize: *self.players[prize_index].prize(),
+        });
+
+        let swapped_prize_winner = if winning_player_index != prize_index {
+            let prize1 = self.players[winning_player_index].take_prize();
+            let prize2 = self.players[prize_index].take_prize();
+            self.players[winning_player_index].set_prize(prize2);
+            self.players[prize_index].set_prize(prize1);
+            Some(winning_player_index)
+        } else {
+            None
+        };
+        self.put_cards_away(swapped_prize_winner, rng, events);
+
+        self.first_player = winning_player_index;
+        self.start_round(rng, events);
+        Ok(())
+    }
+    /// The current player, who has no legal turn, passes by the rules for
+    /// having no legal turn. The round ends with no winner if the rules say
+    /// so, or once every player has passed in a row, and the next round begins
+    /// with the next player playing first.
+    pub(crate) fn pass_turn<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) -> Result<Pass, RuleError> {
+        if self.phase != Phase::Playing {
+            return Err(RuleError::RoundNotInProgress);
+        }
+        if !self.legal_turns().is_empty() {
+            return Err(RuleError::MustPlay);
+        }
+        let player_index = self.current_player;
+        let player = &mut self.players[player_index];
+        let discarded = if self.rules.no_legal_turn == NoLegalTurn::DiscardToDeck
+            && !player.hand().is_empty()
+        {
+            let card = player.take_card_from_hand(0);
+            player.put_card_under_deck(card);
+            Some(card)
+        } else {
+            None
+        };
+        events.record(GameEvent::TurnPassed {
+            player_index,
+            discarded,
+        });
+
+        self.passes_in_a_row += 1;
+        let num_players = self.num_players();
+        let round_ended = self.rules.no_legal_turn == NoLegalTurn::EndRound
+            || self.passes_in_a_row >= num_players;
+        if round_ended {
+            events.record(GameEvent::RoundEndedWithoutWinner);
+            self.put_cards_away(None, rng, events);
+            self.first_player = (self.first_player + 1) % num_players;
+            self.start_round(rng, events);
+        } else {
+            self.current_player = (self.current_player + 1) % num_players;
+        }
+        Ok(Pass {
+            discarded,
+            round_ended,
+        })
+    }
+    /// At the end of a round, put away the cards in the hands, fields and
+    /// prizes, keeping the in-season cards from the fields in hand, and shuffle
+    /// the decks. `swapped_prize_winner` is the winner of the round if they
+    /// took another player's prize.
+    fn put_cards_away<R: Rng + ?Sized>(
+        &mut self,
+        swapped_prize_winner: Option<usize>,
+        rng: &mut R,
+        events: &mut impl EventSink,
+    ) {
+        let pile = match self.rules.deck_exhaustion {
+            DeckExhaustion::DrawRemaining => Pile::Deck,
+            DeckExhaustion::ReshuffleDiscards => Pile::Discard,
+        };
+        for i in 0..self.players.len() {
+            let player = &mut self.players[i];
+
+            player.move_hand_to(pile);
+            let returned_to_hand = player.remove_cards_from_field(pile);
+            events.record(GameEvent::FieldCleared {
+                player_index: i,
+                returned_to_hand,
+            });
+
+            if let Some(prize) = player.take_prize() {
+                // If the winning player swaps prizes and the received prize is
+                // of their season, it is added to their hand instead of their deck
+                if self.rules.own_season_prize_to_hand
+                    && swapped_prize_winner == Some(i)
+                    && prize.season() == player.season()
+                {
+                    player.add_card_to_hand(prize);
+                } else {
+                    player.add_card_to_pile(prize, pile);
+                }
+            }
+
+            player.shuffle_deck(rng);
+        }
+    }
+    /// Perform the play, removing the card from the player's hand and playing it
+    /// in the correct location
+    fn execute_turn(&mut self, turn: &Turn, events: &mut impl EventSink) {
+        let card = self.players[turn.player_index].take_card_from_hand(turn.card_index_in_hand);
+        events.record(GameEvent::CardPlayed { turn: *turn, card });
+        let possible_other_card =
+            self.players[turn.field_index].play_card(card, turn.spot_on_field);
+        if let Some(other_card) = possible_other_card {
+            events.record(GameEvent::CardSwappedToHand {
+                player_index: turn.player_index,
+                card: other_card,
+            });
+            self.players[turn.player_index].add_card_to_hand(other_card);
+        }
+    }
+    /// The win condition the turn met using only cards of the field owner's
+    /// season, which wins the whole game if the rules say so
+    pub(crate) fn in_season_win(&self, turn: &Turn) -> Option<WinCondition> {
+        let player_played_on = &self.players[turn.field_index];
+        let field = player_played_on.field();
+        let spot = turn.spot_on_field;
+        let player_season = player_played_on.season();
+        let card = field.get(spot).as_ref()?;
+        if !self.rules.in_season_win_ends_game || card.season() != player_season {
+            return None;
+        }
+        // If there is a win condition on the in-season field, then it is a game win
+        let field_in_season = field.clone_in_season(player_season);
+        check_win(&field_in_season, &spot, card, self.rules.point_threshold)
+    }
+    /// Check first for a game-winning condition, then for a round-winning condition
+    fn check_for_win_conditions(&self, turn: &Turn) -> Option<WinState> {
+        let field_index = turn.field_index;
+        let player_played_on = &self.players[field_index];
+        let field = player_played_on.field();
+        let spot = turn.spot_on_field;
+        let player_season = player_played_on.season();
+        let card = field.get(spot).as_ref()?;
+
+        if let Some(condition) = self.in_season_win(turn) {
+            return Some(WinState {
+                player_index: field_index,
+                game_won: true,
+                condition,
+            });
+        }
+
+        let opt_win_cond = check_win(field, &spot, card, self.rules.point_threshold);
+        if let Some(condition) = opt_win_cond {
+            let game_won = self.rules.two_ancients_win_game
+                && check_two_ancients_house_rule(&field.court, &condition, player_season);
+            Some(WinState {
+                player_index: field_index,
+                game_won,
+                condition,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::StdRng;
+
+    use super::super::{
+        card::{all_cards, rune::Rune},
+        field::{Field, Row, Spot},
+    };
+    use super::*;
+
+    /// A deck of 11 cards: the first card becomes the prize and the rest are drawn
+    /// into the hand, so the given card ends up first in hand
+    fn deck_with_first_in_hand(prize: Card, first_in_hand: Card) -> Vec<Card> {
+        let mut deck = vec![prize, first_in_hand];
+        deck.resize(11, Card::create_archer(Season::Ferric, 8));
+        deck
+    }
+
+    #[test]
+    fn test_round_is_won_and_completed() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring_prize = Card::create_queen(Season::Spring, 3);
+        let autumn_prize = Card::create_warrior(Season::Spring, 9);
+        state.players[0].set_deck(deck_with_first_in_hand(
+            spring_prize,
+            Card::create_countess(Season::Ferric),
+        ));
+        state.players[0].play_card(Card::create_count(Season::Winter), Spot::new(Row::Court, 0));
+        state.players[1].set_deck(deck_with_first_in_hand(
+            autumn_prize,
+            Card::create_beast(Season::Autumn, 8),
+        ));
+        state.start_round(&mut rng, &mut ());
+
+        let win_state = WinState {
+            player_index: 0,
+            game_won: false,
+            condition: WinCondition::CountCountess([
+                Spot::new(Row::Court, 0),
+                Spot::new(Row::Court, 1),
+            ]),
+        };
+        let turn = Turn {
+            player_index: 0,
+            field_index: 0,
+            card_index_in_hand: 0,
+            spot_on_field: Spot::new(Row::Court, 1),
+        };
+        assert_eq!(
+            Ok(Outcome::Win(win_state.clone())),
+            state.apply_turn(&turn, &mut ())
+        );
+        assert_eq!(&Phase::ChoosingPrize(win_state), state.phase());
+        assert_eq!(
+            Err(RuleError::RoundNotInProgress),
+            state.apply_turn(&turn, &mut ())
+        );
+
+        // Spring swaps prizes with Autumn, and gets an in-season card back in hand
+        assert_eq!(
+            Err(RuleError::NoSuchPrize(2)),
+            state.choose_prize(2, &mut rng, &mut ())
+        );
+        assert_eq!(Ok(()), state.choose_prize(1, &mut rng, &mut ()));
+        assert_eq!(2, state.round());
+        assert_eq!(&Phase::Playing, state.phase());
+        assert_eq!(0, state.current_player());
+        assert!(state.players[0].hand().contains(&autumn_prize));
+        assert_eq!(&Field::new(), state.players[0].field());
+        assert_eq!(
+            Err(RuleError::NoPrizeToChoose),
+            state.choose_prize(0, &mut rng, &mut ())
+        );
+    }
+
+    /// Set up a round that Spring wins by playing the Countess in their hand
+    /// next to the Count on their court, with the given decks
+    fn count_countess_round(
+        rules: RuleSet,
+        spring_deck: Vec<Card>,
+        autumn_deck: Vec<Card>,
+    ) -> GameState {
+        let mut state = GameState::new(2, rules);
+        state.players[0].set_deck(spring_deck);
+        state.players[0].play_card(Card::create_count(Season::Winter), Spot::new(Row::Court, 0));
+        state.players[1].set_deck(autumn_deck);
+        state.start_round(&mut StdRng::seed_from_u64(0), &mut ());
+        let countess_index = state.players[0]
+            .hand()
+            .iter()
+            .position(|c| c.rune() == Rune::Countess)
+            .expect("The Countess should be in hand");
+        let turn = Turn {
+            player_index: 0,
+            field_index: 0,
+            card_index_in_hand: countess_index,
+            spot_on_field: Spot::new(Row::Court, 1),
+        };
+        assert!(matches!(
+            state.apply_turn(&turn, &mut ()),
+            Ok(Outcome::Win(_))
+        ));
+        state
+    }
+
+    #[test]
+    fn test_round_without_prizes_when_the_decks_run_out() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut state = count_countess_round(
+            RuleSet::standard(),
+            vec![Card::create_countess(Season::Ferric)],
+            vec![
+                Card::create_queen(Season::Autumn, 3),
+                Card::create_beast(Season::Autumn, 8),
+            ],
+        );
+        assert_eq!(&None, state.players[0].prize());
+        assert_eq!(2, state.players[1].hand().len());
+
+        // Nobody has a prize, so Spring can only keep their own lack of one
+        assert_eq!(
+            Err(RuleError::NoSuchPrize(1)),
+            state.choose_prize(1, &mut rng, &mut ())
+        );
+        assert_eq!(Ok(()), state.choose_prize(0, &mut rng, &mut ()));
+        assert_eq!(2, state.round());
+        assert_eq!(2, state.players[0].hand().len());
+        assert_eq!(&None, state.players[1].prize());
+    }
+
+    #[test]
+    fn test_discards_are_reshuffled_when_the_deck_runs_short() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let left_in_deck = Card::create_queen(Season::Spring, 3);
+        let rules = RuleSet {
+            hand_size: 2,
+            deck_exhaustion: DeckExhaustion::ReshuffleDiscards,
+            ..RuleSet::standard()
+        };
+        let mut state = count_countess_round(
+            rules,
+            vec![
+                left_in_deck,
+                Card::create_mist(),
+                Card::create_beast(Season::Winter, 8),
+                Card::create_countess(Season::Ferric),
+            ],
+            vec![
+                Card::create_queen(Season::Autumn, 3),
+                Card::create_beast(Season::Autumn, 8),
+                Card::create_archer(Season::Autumn, 4),
+            ],
+        );
+        assert_eq!(1, state.players[0].deck().len());
+
+        // Spring's hand, field and prize are discarded, and only the card left
+        // in their deck is drawn before the discards
+        assert_eq!(Ok(()), state.choose_prize(0, &mut rng, &mut ()));
+        let spring = &state.players[0];
+        assert!(spring.discard_pile().is_empty());
+        assert!(spring.hand().contains(&left_in_deck));
+        assert_eq!(2, spring.hand().len());
+        assert!(spring.prize().is_some());
+        assert_eq!(2, spring.deck().len());
+    }
+
+    #[test]
+    fn test_players_with_no_legal_turn_pass() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // Spring holds only a Mist with nothing to swap with, and Autumn
+        // nothing at all
+        let stuck = |no_legal_turn| {
+            let mut state = GameState::new(
+                2,
+                RuleSet {
+                    no_legal_turn,
+                    ..RuleSet::standard()
+                },
+            );
+            state.start_round(&mut StdRng::seed_from_u64(0), &mut ());
+            state.players[0].add_card_to_hand(Card::create_mist());
+            state
+        };
+
+        let mut state = stuck(NoLegalTurn::Pass);
+        assert!(state.legal_turns().is_empty());
+        assert_eq!(
+            Ok(Pass {
+                discarded: None,
+                round_ended: false
+            }),
+            state.pass_turn(&mut rng, &mut ())
+        );
+        assert_eq!(1, state.current_player());
+        // Once every player has passed, the round ends with the next player
+        // playing first
+        assert_eq!(
+            Ok(Pass {
+                discarded: None,
+                round_ended: true
+            }),
+            state.pass_turn(&mut rng, &mut ())
+        );
+        assert_eq!(2, state.round());
+        assert_eq!(1, state.current_player());
+
+        let mut state = stuck(NoLegalTurn::DiscardToDeck);
+        assert_eq!(
+            Ok(Pass {
+                discarded: Some(Card::create_mist()),
+                round_ended: false
+            }),
+            state.pass_turn(&mut rng, &mut ())
+        );
+        assert!(state.players[0].hand().is_empty());
+        assert_eq!(&vec![Card::create_mist()], state.players[0].deck());
+
+        let mut state = stuck(NoLegalTurn::EndRound);
+        assert!(state.pass_turn(&mut rng, &mut ()).unwrap().round_ended);
+        assert_eq!(2, state.round());
+
+        // A player who can play may not pass
+        let mut state = stuck(NoLegalTurn::Pass);
+        state.players[0].add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        assert_eq!(Err(RuleError::MustPlay), state.pass_turn(&mut rng, &mut ()));
+    }
+
+    #[test]
+    fn test_in_season_win_only_ends_the_game_when_the_rules_say_so() {
+        let in_season_win = |rules| {
+            let mut state = GameState::new(2, rules);
+            state.players[0].set_deck(deck_with_first_in_hand(
+                Card::create_queen(Season::Spring, 3),
+                Card::create_countess(Season::Spring),
+            ));
+            state.players[0]
+                .play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+            state.players[1].set_deck(deck_with_first_in_hand(
+                Card::create_queen(Season::Autumn, 3),
+                Card::create_beast(Season::Autumn, 8),
+            ));
+            state.start_round(&mut StdRng::seed_from_u64(0), &mut ());
+            let turn = Turn {
+                player_index: 0,
+                field_index: 0,
+                card_index_in_hand: 0,
+                spot_on_field: Spot::new(Row::Court, 1),
+            };
+            match state.apply_turn(&turn, &mut ()) {
+                Ok(Outcome::Win(win_state)) => {
+                    assert_eq!(win_state.game_won, state.in_season_win(&turn).is_some());
+                    win_state.game_won
+                }
+                other => panic!("Expected a win, found {:?}", other),
+            }
+        };
+        assert!(in_season_win(RuleSet::standard()));
+        assert!(!in_season_win(RuleSet {
+            in_season_win_ends_game: false,
+            ..RuleSet::standard()
+        }));
+    }
+
+    #[test]
+    fn test_determinized_state_only_moves_the_unseen_cards() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let state = GameState::new_game(3, RuleSet::standard(), all_cards(), &mut rng, &mut ());
+        let imagined = state.determinize(1, &mut rng);
+        assert_ne!(state, imagined);
+        for (player, imagined_player) in state.players.iter().zip(&imagined.players) {
+            assert_eq!(player.field(), imagined_player.field());
+            assert_eq!(player.prize(), imagined_player.prize());
+            assert_eq!(player.hand().len(), imagined_player.hand().len());
+            assert_eq!(player.deck().len(), imagined_player.deck().len());
+        }
+        assert_eq!(state.players[1].hand(), imagined.players[1].hand());
+
+        let mut unseen = state.unseen_cards(1);
+        let mut imagined_unseen = imagined.unseen_cards(1);
+        unseen.sort_by_key(|c| c.to_text());
+        imagined_unseen.sort_by_key(|c| c.to_text());
+        assert_eq!(unseen, imagined_unseen);
+    }
+
+    #[test]
+    fn test_two_and_three_player_games_are_dealt_and_played() {
+        for (num_players, seasons) in [
+            (2, vec![Season::Spring, Season::Autumn]),
+            (3, vec![Season::Spring, Season::Summer, Season::Autumn]),
+        ] {
+            let mut rng = StdRng::seed_from_u64(num_players as u64);
+            let mut state = GameState::new_game(
+                num_players,
+                RuleSet::standard(),
+                all_cards(),
+                &mut rng,
+                &mut (),
+            );
+            let seated: Vec<Season> = state.players.iter().map(|p| p.season()).collect();
+            assert_eq!(seasons, seated);
+            // Every card is dealt, in even shares, less the hand and the prize
+            for player in &state.players {
+                assert_eq!(10, player.hand().len());
+                assert!(player.prize().is_some());
+                assert_eq!(120 / num_players - 11, player.deck().len());
+            }
+
+            for i in 0..num_players {
+                assert_eq!(i, state.current_player());
+                let turn = *state
+                    .legal_turns()
+                    .iter()
+                    .find(|t| t.field_index == i)
+                    .expect("Each player can play on their own empty field");
+                assert_eq!(Ok(Outcome::NextTurn), state.apply_turn(&turn, &mut ()));
+            }
+            // Everyone has played a card onto their own field, and play is back
+            // with the first player
+            assert_eq!(0, state.current_player());
+            for player in &state.players {
+                assert_eq!(9, player.hand().len());
+                assert_eq!(120 / num_players - 11, player.deck().len());
+            }
+            let on_fields: usize = state
+                .players
+                .iter()
+                .map(|p| {
+                    p.field()
+                        .garden
+                        .iter()
+                        .chain(&p.field().court)
+                        .flatten()
+                        .count()
+                })
+                .sum();
+            assert_eq!(num_players, on_fields);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_deals_same_decks() {
+        let deal = |seed| {
+            let mut state = GameState::new(3, RuleSet::standard());
+            let mut rng = StdRng::seed_from_u64(seed);
+            state.deal(all_cards(), &mut rng, &mut ());
+            state.start_round(&mut rng, &mut ());
+            state
+        };
+        assert_eq!(deal(42), deal(42));
+        assert_ne!(deal(42), deal(43));
+    }
+
+    #[test]
+    fn test_cloned_state_can_be_played_on_without_changing_the_original() {
+        let mut state = GameState::new(4, RuleSet::standard());
+        let mut rng = StdRng::seed_from_u64(5);
+        state.deal(all_cards(), &mut rng, &mut ());
+        state.start_round(&mut rng, &mut ());
+
+        let mut copy = state.clone();
+        let turn = copy.legal_turns()[0];
+        assert!(copy.apply_turn(&turn, &mut ()).is_ok());
+        assert_ne!(state, copy);
+        assert_eq!(0, state.current_player());
+        assert_eq!(10, state.players[0].hand().len());
+    }
+
+    #[test]
+    fn test_illegal_turns_are_rejected_without_changing_anything() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        state.players[0].add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        state.players[0].add_card_to_hand(Card::create_changeling(Season::Spring));
+        state.players[0].play_card(
+            Card::create_beast(Season::Spring, 8),
+            Spot::new(Row::Court, 0),
+        );
+        state.players[1].play_card(
+            Card::create_archer(Season::Autumn, 4),
+            Spot::new(Row::Garden, 0),
+        );
+        let state_before = state.clone();
+
+        let turn = |card_index_in_hand, field_index, spot_on_field| Turn {
+            player_index: 0,
+            field_index,
+            card_index_in_hand,
+            spot_on_field,
+        };
+        let cases = [
+            (
+                turn(0, 0, Spot::new(Row::Court, 0)),
+                RuleError::SpotOccupied(Spot::new(Row::Court, 0)),
+            ),
+            (
+                turn(0, 1, Spot::new(Row::Court, 0)),
+                RuleError::NotOwnField(Rune::Queen),
+            ),
+            (
+                turn(1, 1, Spot::new(Row::Garden, 0)),
+                RuleError::CannotSwap {
+                    card: Rune::Changeling,
+                    other_card: Rune::Archer,
+                },
+            ),
+            (
+                turn(1, 1, Spot::new(Row::Garden, 1)),
+                RuleError::NothingToSwap(Spot::new(Row::Garden, 1)),
+            ),
+            (
+                turn(2, 0, Spot::new(Row::Court, 1)),
+                RuleError::NoSuchCardInHand {
+                    index: 2,
+                    hand_size: 2,
+                },
+            ),
+            (
+                turn(0, 2, Spot::new(Row::Court, 1)),
+                RuleError::NoSuchField(2),
+            ),
+            (
+                Turn {
+                    player_index: 1,
+                    ..turn(0, 1, Spot::new(Row::Court, 1))
+                },
+                RuleError::NotPlayersTurn {
+                    expected: 0,
+                    found: 1,
+                },
+            ),
+        ];
+        for (turn, error) in cases {
+            assert_eq!(Err(error), state.apply_turn(&turn, &mut ()));
+            assert_eq!(state_before, state);
+        }
+
+        assert_eq!(
+            Ok(Outcome::NextTurn),
+            state.apply_turn(&turn(0, 0, Spot::new(Row::Court, 1)), &mut ())
+        );
+        assert_eq!(1, state.current_player());
+    }
+}
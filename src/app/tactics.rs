@@ -0,0 +1,697 @@
+use super::{
+    card::{rune::Rune, score::Score, Card},
+    field::{Field, Spot},
+    rules::RuleSet,
+    score_breakdown::ScoreBreakdown,
+    season::Season,
+    state::{GameState, Outcome, Phase, WinState},
+    turn::{get_valid_spots_from_card, Turn},
+    win_condition::{check_win, WinCondition},
+};
+
+/// What a turn would do, as far as the player taking it can tell
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct TurnAssessment {
+    pub turn: Turn,
+    pub card: Card,
+    /// The round or game the turn wins, and for whom
+    pub win: Option<WinState>,
+    /// For each player, how many cards would win them the round if played
+    /// next: the cards in their own hand, and the swap cards in the hands of
+    /// the others, leaving out the player taking the turn's own hand when
+    /// counting for anyone else.
+    pub threats: Vec<usize>,
+    /// The points on each player's field after the turn
+    pub points: Vec<i32>,
+}
+
+/// What a prize card put straight into the winner's hand is worth on top of
+/// the card, since it can be played in the very next round
+const TO_HAND_VALUE: f64 = 1.0;
+/// What a card of a player's own season is worth to them on top of the card,
+/// when winning with only in-season cards wins the game
+const IN_SEASON_VALUE: f64 = 1.0;
+
+/// What taking a prize would do for the round's winner
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct PrizeAssessment {
+    /// The player whose prize is taken. Taking the winner's own prize leaves
+    /// every prize where it is.
+    pub prize_index: usize,
+    pub prize: Option<Card>,
+    /// The winner's own prize, which the other player gets in exchange
+    pub given: Option<Card>,
+    /// Whether the prize goes straight into the winner's hand
+    pub to_hand: bool,
+    /// How much the winner gains on the other player by the exchange, next to
+    /// keeping their own prize
+    pub value: f64,
+}
+
+/// A win that a card would make on a field, by what it is made of rather
+/// than where
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum NearWin {
+    CountCountess,
+    /// Three of the rune in the court, with any Ancients standing in for it
+    ThreeInCourt(Rune),
+    TwoPlagues,
+    Points,
+}
+impl NearWin {
+    /// What the win condition met on the field is made of
+    fn of_win(field: &Field, condition: &WinCondition) -> Self {
+        match condition {
+            WinCondition::CountCountess(_) => NearWin::CountCountess,
+            WinCondition::ThreeInCourt(spots) => NearWin::ThreeInCourt(
+                spots
+                    .iter()
+                    .filter_map(|&spot| field.get(spot).map(|card| card.rune()))
+                    .find(|&rune| rune != Rune::Ancient)
+                    .unwrap_or(Rune::Ancient),
+            ),
+            WinCondition::TwoPlagues(_) => NearWin::TwoPlagues,
+            WinCondition::FourtyPoints => NearWin::Points,
+        }
+    }
+}
+
+/// Something a turn does that makes it worth playing, or not
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Reason {
+    /// Wins the round, or the whole game, for the player taking the turn,
+    /// leaving their field with the given points
+    Wins {
+        condition: WinCondition,
+        game_won: bool,
+        points: i32,
+    },
+    /// Wins the round for another player, by swapping onto their field
+    LetsWin { player_index: usize, win: NearWin },
+    /// Leaves fewer cards that would win another player the round, swapping
+    /// away the card `taken` from their field if there was one. The near wins
+    /// are the ones the player had before the turn.
+    Breaks {
+        player_index: usize,
+        near_wins: Vec<NearWin>,
+        taken: Option<Card>,
+        threats_before: usize,
+        threats_after: usize,
+    },
+    /// Leaves more cards that would win another player the round
+    Helps { player_index: usize, threats: usize },
+    /// Leaves cards that would win the player the round, from their hand or
+    /// swapped onto their field by someone else, making the given near wins
+    SetsUp {
+        threats: usize,
+        near_wins: Vec<NearWin>,
+    },
+    /// Adds points to the player's own field
+    AddsPoints { points: i32 },
+}
+
+/// A turn worth considering, with what it does
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Suggestion {
+    pub turn: Turn,
+    pub card: Card,
+    pub reasons: Vec<Reason>,
+}
+
+/// Counts how many cards would win each player the round, from the cards
+/// that could be played onto their field before it is over
+struct ThreatCounter {
+    seat: usize,
+    point_threshold: i32,
+}
+impl ThreatCounter {
+    fn new(state: &GameState) -> Self {
+        Self {
+            seat: state.current_player(),
+            point_threshold: state.rules().point_threshold,
+        }
+    }
+    fn cards(&self, state: &GameState, player_index: usize) -> Vec<(Card, usize)> {
+        threat_cards(state, self.seat, player_index)
+    }
+    fn count(&self, state: &GameState, player_index: usize) -> usize {
+        let field = state.players()[player_index].field();
+        count_winning_cards(
+            field,
+            &self.cards(state, player_index),
+            self.point_threshold,
+        )
+    }
+    fn near_wins(&self, state: &GameState, player_index: usize) -> Vec<NearWin> {
+        let field = state.players()[player_index].field();
+        near_wins(
+            field,
+            &self.cards(state, player_index),
+            self.point_threshold,
+        )
+    }
+    fn count_all(&self, state: &GameState) -> Vec<usize> {
+        (0..state.num_players())
+            .map(|i| self.count(state, i))
+            .collect()
+    }
+}
+
+/// The points on each player's field
+fn field_points(state: &GameState) -> Vec<i32> {
+    state
+        .players()
+        .iter()
+        .map(|player| ScoreBreakdown::of_field(player.field()).total())
+        .collect()
+}
+
+/// Try out each of the current player's legal turns on a copy of the state.
+/// Turns that play the same card in the same spot are only tried once.
+pub(crate) fn assess_turns(state: &GameState) -> Vec<TurnAssessment> {
+    let seat = state.current_player();
+    let threat_counter = ThreatCounter::new(state);
+    let threats_before = threat_counter.count_all(state);
+    let points_before = field_points(state);
+
+    let hand = state.players()[seat].hand();
+    let mut assessments: Vec<TurnAssessment> = vec![];
+    for turn in state.legal_turns() {
+        let card = hand[turn.card_index_in_hand];
+        if assessments.iter().any(|a| {
+            a.card == card
+                && a.turn.field_index == turn.field_index
+                && a.turn.spot_on_field == turn.spot_on_field
+        }) {
+            continue;
+        }
+        let mut after = state.clone();
+        let outcome = after
+            .apply_turn(&turn, &mut ())
+            .expect("Legal turns can be played");
+        let mut threats = threats_before.clone();
+        let mut points = points_before.clone();
+        points[turn.field_index] =
+            ScoreBreakdown::of_field(after.players()[turn.field_index].field()).total();
+        let win = match outcome {
+            Outcome::Win(win_state) => Some(win_state),
+            Outcome::NextTurn => {
+                // Only the field played on and the player's hand have changed
+                threats[turn.field_index] = threat_counter.count(&after, turn.field_index);
+                threats[seat] = threat_counter.count(&after, seat);
+                None
+            }
+        };
+        assessments.push(TurnAssessment {
+            turn,
+            card,
+            win,
+            threats,
+            points,
+        });
+    }
+    assessments
+}
+
+/// What the current player's turn does, going by its assessment
+pub(crate) fn explain_turn(state: &GameState, assessment: &TurnAssessment) -> Vec<Reason> {
+    let seat = state.current_player();
+    let turn = &assessment.turn;
+    let mut after = state.clone();
+    after
+        .apply_turn(turn, &mut ())
+        .expect("Legal turns can be played");
+    match &assessment.win {
+        Some(win_state) if win_state.player_index == seat => {
+            return vec![Reason::Wins {
+                condition: win_state.condition,
+                game_won: win_state.game_won,
+                points: assessment.points[seat],
+            }]
+        }
+        Some(win_state) => {
+            let field = after.players()[win_state.player_index].field();
+            return vec![Reason::LetsWin {
+                player_index: win_state.player_index,
+                win: NearWin::of_win(field, &win_state.condition),
+            }];
+        }
+        None => {}
+    }
+    let threat_counter = ThreatCounter::new(state);
+    let threats_before = threat_counter.count_all(state);
+    let mut reasons = vec![];
+    for (player_index, (&before, &after)) in threats_before
+        .iter()
+        .zip(&assessment.threats)
+        .enumerate()
+        .filter(|(i, _)| *i != seat)
+    {
+        if after < before {
+            let taken = if player_index == turn.field_index {
+                *state.players()[player_index]
+                    .field()
+                    .get(turn.spot_on_field)
+            } else {
+                None
+            };
+            reasons.push(Reason::Breaks {
+                player_index,
+                near_wins: threat_counter.near_wins(state, player_index),
+                taken,
+                threats_before: before,
+                threats_after: after,
+            });
+        } else if after > before {
+            reasons.push(Reason::Helps {
+                player_index,
+                threats: after,
+            });
+        }
+    }
+    if assessment.threats[seat] > 0 {
+        reasons.push(Reason::SetsUp {
+            threats: assessment.threats[seat],
+            near_wins: threat_counter.near_wins(&after, seat),
+        });
+    }
+    let points_before = field_points(state)[seat];
+    if assessment.points[seat] > points_before {
+        reasons.push(Reason::AddsPoints {
+            points: assessment.points[seat],
+        });
+    }
+    reasons
+}
+
+/// Weigh up each prize the round's winner may take. The winner's own prize
+/// comes first, and is worth nothing, since nothing changes hands. Another
+/// player's prize is worth what the winner gains by swapping for it, such as
+/// a card of their season going straight to their hand, plus what that player
+/// loses, such as a Ferric Ancient or a Plague.
+pub(crate) fn assess_prizes(state: &GameState) -> Vec<PrizeAssessment> {
+    let Phase::ChoosingPrize(win_state) = state.phase() else {
+        return vec![];
+    };
+    let winner = win_state.player_index;
+    let rules = state.rules();
+    let players = state.players();
+    let own_prize = *players[winner].prize();
+    let worth = |card: &Option<Card>, holder: usize| {
+        card.map_or(0.0, |card| {
+            card_value(&card, players[holder].season(), rules)
+        })
+    };
+
+    let keep = PrizeAssessment {
+        prize_index: winner,
+        prize: own_prize,
+        given: None,
+        to_hand: false,
+        value: 0.0,
+    };
+    let swaps = state
+        .prize_choices()
+        .into_iter()
+        .filter(|&i| i != winner)
+        .map(|prize_index| {
+            let prize = *players[prize_index].prize();
+            let to_hand = rules.own_season_prize_to_hand
+                && prize.is_some_and(|card| card.season() == players[winner].season());
+            let gained = worth(&prize, winner) - worth(&own_prize, winner)
+                + if to_hand { TO_HAND_VALUE } else { 0.0 };
+            let lost = worth(&prize, prize_index) - worth(&own_prize, prize_index);
+            PrizeAssessment {
+                prize_index,
+                prize,
+                given: own_prize,
+                to_hand,
+                value: gained + lost,
+            }
+        });
+    std::iter::once(keep).chain(swaps).collect()
+}
+
+/// The index of the player whose prize the round's winner is best off
+/// taking, keeping their own unless another is worth more
+pub(crate) fn best_prize(state: &GameState) -> Option<usize> {
+    assess_prizes(state)
+        .into_iter()
+        .reduce(|best, a| if a.value > best.value { a } else { best })
+        .map(|a| a.prize_index)
+}
+
+/// How much a card is worth to a player of the given season. The cards that
+/// win on their own, the Ancients and Plagues, are worth the most, then the
+/// Counts and Countesses and the cards that change other cards, then the rest
+/// by their points.
+fn card_value(card: &Card, season: Season, rules: &RuleSet) -> f64 {
+    let points = |score: Score| match score {
+        Score::Value(points) => points,
+        Score::Mod(_) => 0,
+    };
+    let value = match card.rune() {
+        Rune::Ancient if card.season() == Season::Ferric && rules.two_ancients_win_game => 4.0,
+        Rune::Ancient | Rune::Plague => 3.0,
+        Rune::Count | Rune::Countess => 2.0,
+        Rune::Changeling | Rune::Mist | Rune::Weather => 1.5,
+        _ => points(card.garden_score()).max(points(card.court_score())) as f64 / 5.0,
+    };
+    if rules.in_season_win_ends_game && card.season() == season {
+        value + IN_SEASON_VALUE
+    } else {
+        value
+    }
+}
+
+/// Group the cards that play alike, whatever their season, with how many
+/// of each there are. Seasons only matter for whether a win ends the game, so
+/// a card of each group stands in for the rest when looking for wins.
+pub(crate) fn count_copies(cards: &[Card]) -> Vec<(Card, usize)> {
+    let plays_alike = |a: &Card, b: &Card| {
+        a.rune() == b.rune()
+            && a.garden_score() == b.garden_score()
+            && a.court_score() == b.court_score()
+    };
+    let mut copies: Vec<(Card, usize)> = vec![];
+    for card in cards {
+        match copies.iter_mut().find(|(c, _)| plays_alike(c, card)) {
+            Some((_, count)) => *count += 1,
+            None => copies.push((*card, 1)),
+        }
+    }
+    copies
+}
+
+/// How many of the given cards would win a round on the field, if its owner
+/// played one of them, or anyone swapped one onto it. The more there are, the
+/// nearer the field is to a win.
+pub(crate) fn count_winning_cards(
+    field: &Field,
+    cards: &[(Card, usize)],
+    point_threshold: i32,
+) -> usize {
+    cards
+        .iter()
+        .filter(|(card, _)| winning_play(field, card, point_threshold).is_some())
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// The cards that could be played onto the field of the player at
+/// `player_index` before the round is over, as the player at `seat` weighs it
+/// up. Hands are only filled between rounds, so these are the cards in the
+/// player's own hand, and the swap cards in the hands of everyone else other
+/// than `seat`, who decides for themselves where their cards go.
+pub(crate) fn threat_cards(
+    state: &GameState,
+    seat: usize,
+    player_index: usize,
+) -> Vec<(Card, usize)> {
+    let players = state.players();
+    let swaps = players
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != player_index && i != seat)
+        .flat_map(|(_, player)| player.hand())
+        .filter(|card| card.rune().ability().is_swap());
+    let cards: Vec<Card> = players[player_index]
+        .hand()
+        .iter()
+        .chain(swaps)
+        .copied()
+        .collect();
+    count_copies(&cards)
+}
+
+/// The different wins the given cards would make on the field, if its owner
+/// played one of them, or anyone swapped one onto it
+pub(crate) fn near_wins(
+    field: &Field,
+    cards: &[(Card, usize)],
+    point_threshold: i32,
+) -> Vec<NearWin> {
+    let mut wins = vec![];
+    for (card, _) in cards {
+        if let Some((spot, condition)) = winning_play(field, card, point_threshold) {
+            let mut field_after = *field;
+            field_after.set(Some(*card), spot);
+            let win = NearWin::of_win(&field_after, &condition);
+            if !wins.contains(&win) {
+                wins.push(win);
+            }
+        }
+    }
+    wins
+}
+
+/// A spot on the field where its owner could play the card, or anyone could
+/// swap it, to win the round, and the win it would make
+pub(crate) fn winning_play(
+    field: &Field,
+    card: &Card,
+    point_threshold: i32,
+) -> Option<(Spot, WinCondition)> {
+    let spots = get_valid_spots_from_card(0, card, &[field]).swap_remove(0);
+    spots.into_iter().find_map(|spot| {
+        let mut field_after = *field;
+        field_after.set(Some(*card), spot);
+        check_win(&field_after, &spot, card, point_threshold).map(|condition| (spot, condition))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::field::Row;
+    use super::*;
+
+    /// A three player game where Spring has just won the round, with the
+    /// given prizes showing
+    fn prize_round(prizes: [Card; 3]) -> GameState {
+        let mut state = GameState::new(3, RuleSet::house());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.add_card_to_hand(Card::create_countess(Season::Ferric));
+        for (player, prize) in state.players_mut().iter_mut().zip(prizes) {
+            player.set_prize(Some(prize));
+        }
+        let turn = Turn {
+            player_index: 0,
+            field_index: 0,
+            card_index_in_hand: 0,
+            spot_on_field: Spot::new(Row::Court, 1),
+        };
+        assert!(matches!(
+            state.apply_turn(&turn, &mut ()),
+            Ok(Outcome::Win(_))
+        ));
+        state
+    }
+
+    #[test]
+    fn test_explains_what_turns_do() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(Card::create_count(Season::Spring), Spot::new(Row::Court, 0));
+        spring.add_card_to_hand(Card::create_countess(Season::Ferric));
+        spring.add_card_to_hand(Card::create_mist());
+        let autumn = &mut state.players_mut()[1];
+        autumn.play_card(Card::create_count(Season::Autumn), Spot::new(Row::Court, 2));
+        for _ in 0..3 {
+            autumn.add_card_to_hand(Card::create_countess(Season::Ferric));
+        }
+
+        let assessments = assess_turns(&state);
+        let reasons_for = |card: Card, field_index: usize, spot: Spot| {
+            let assessment = assessments
+                .iter()
+                .find(|a| {
+                    a.card == card
+                        && a.turn.field_index == field_index
+                        && a.turn.spot_on_field == spot
+                })
+                .expect("The turn should be legal");
+            explain_turn(&state, assessment)
+        };
+        assert_eq!(
+            vec![Reason::Wins {
+                condition: WinCondition::CountCountess([
+                    Spot::new(Row::Court, 0),
+                    Spot::new(Row::Court, 1)
+                ]),
+                game_won: false,
+                points: 20,
+            }],
+            reasons_for(
+                Card::create_countess(Season::Ferric),
+                0,
+                Spot::new(Row::Court, 1)
+            )
+        );
+        // The Countess left in hand still wins next turn
+        assert_eq!(
+            vec![
+                Reason::Breaks {
+                    player_index: 1,
+                    near_wins: vec![NearWin::CountCountess],
+                    taken: Some(Card::create_count(Season::Autumn)),
+                    threats_before: 3,
+                    threats_after: 0,
+                },
+                Reason::SetsUp {
+                    threats: 1,
+                    near_wins: vec![NearWin::CountCountess],
+                }
+            ],
+            reasons_for(Card::create_mist(), 1, Spot::new(Row::Court, 2))
+        );
+    }
+
+    #[test]
+    fn test_takes_the_prize_worth_most() {
+        // A Ferric Ancient wins the game with the house rules
+        let state = prize_round([
+            Card::create_queen(Season::Spring, 3),
+            Card::create_archer(Season::Summer, 4),
+            Card::create_ancient(Season::Ferric),
+        ]);
+        assert_eq!(Some(2), best_prize(&state));
+
+        // An in-season card straight to hand beats a stronger card
+        let state = prize_round([
+            Card::create_queen(Season::Ferric, 5),
+            Card::create_queen(Season::Spring, 5),
+            Card::create_warrior(Season::Ferric, 9),
+        ]);
+        let prizes = assess_prizes(&state);
+        assert_eq!(
+            vec![0, 1, 2],
+            prizes.iter().map(|p| p.prize_index).collect::<Vec<_>>()
+        );
+        assert!(prizes[1].to_hand);
+        assert_eq!(Some(1), best_prize(&state));
+
+        // Nothing beats a Plague of the winner's own
+        let state = prize_round([
+            Card::create_plague(Season::Spring),
+            Card::create_queen(Season::Summer, 7),
+            Card::create_count(Season::Autumn),
+        ]);
+        assert_eq!(Some(0), best_prize(&state));
+    }
+
+    #[test]
+    fn test_counts_a_swap_onto_the_players_own_field() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let spring = &mut state.players_mut()[0];
+        spring.play_card(
+            Card::create_plague(Season::Spring),
+            Spot::new(Row::Court, 0),
+        );
+        spring.play_card(
+            Card::create_beast(Season::Spring, 8),
+            Spot::new(Row::Garden, 0),
+        );
+        spring.add_card_to_hand(Card::create_queen(Season::Spring, 3));
+        // Nothing in Spring's hand wins, but Autumn could swap in a second Plague
+        let autumn = &mut state.players_mut()[1];
+        autumn.add_card_to_hand(Card::create_plague(Season::Autumn));
+
+        let threat_counter = ThreatCounter::new(&state);
+        assert_eq!(1, threat_counter.count(&state, 0));
+        assert_eq!(
+            vec![NearWin::TwoPlagues],
+            threat_counter.near_wins(&state, 0)
+        );
+        assert!(matches!(
+            winning_play(
+                state.players()[0].field(),
+                &Card::create_plague(Season::Autumn),
+                40
+            ),
+            Some((_, WinCondition::TwoPlagues(_)))
+        ));
+    }
+
+    #[test]
+    fn test_counts_the_cards_that_would_win_on_a_field() {
+        let mut field = Field::new();
+        field.set(
+            Some(Card::create_count(Season::Spring)),
+            Spot::new(Row::Court, 0),
+        );
+        let cards = count_copies(&[
+            Card::create_countess(Season::Ferric),
+            Card::create_countess(Season::Ferric),
+            Card::create_ancient(Season::Summer),
+            Card::create_count(Season::Winter),
+            Card::create_count(Season::Summer),
+            Card::create_mist(),
+        ]);
+        assert_eq!(4, cards.len());
+        // Both Countesses and the Ancient complete the court
+        assert_eq!(3, count_winning_cards(&field, &cards, 40));
+        assert_eq!(
+            Some((
+                Spot::new(Row::Court, 1),
+                WinCondition::CountCountess([Spot::new(Row::Court, 0), Spot::new(Row::Court, 1)])
+            )),
+            winning_play(&field, &Card::create_countess(Season::Ferric), 40)
+        );
+    }
+
+    #[test]
+    fn test_counts_only_the_cards_that_can_reach_a_field_this_round() {
+        let mut state = GameState::new(3, RuleSet::standard());
+        let summer = &mut state.players_mut()[1];
+        summer.play_card(Card::create_count(Season::Summer), Spot::new(Row::Court, 0));
+        summer.play_card(
+            Card::create_plague(Season::Summer),
+            Spot::new(Row::Court, 4),
+        );
+        summer.play_card(
+            Card::create_beast(Season::Summer, 8),
+            Spot::new(Row::Garden, 0),
+        );
+        summer.set_deck(vec![Card::create_countess(Season::Ferric)]);
+        // Autumn's Countess can only go on Autumn's own field, but their
+        // Plague can be swapped onto Summer's
+        let autumn = &mut state.players_mut()[2];
+        autumn.add_card_to_hand(Card::create_countess(Season::Ferric));
+        autumn.add_card_to_hand(Card::create_plague(Season::Autumn));
+
+        let threat_counter = ThreatCounter::new(&state);
+        assert_eq!(1, threat_counter.count(&state, 1));
+        assert_eq!(
+            vec![NearWin::TwoPlagues],
+            threat_counter.near_wins(&state, 1)
+        );
+
+        // A Countess in Summer's own hand is a threat
+        state.players_mut()[1].add_card_to_hand(Card::create_countess(Season::Summer));
+        assert_eq!(2, threat_counter.count(&state, 1));
+    }
+
+    #[test]
+    fn test_names_the_wins_a_field_is_near() {
+        let mut field = Field::new();
+        field.set(
+            Some(Card::create_queen(Season::Spring, 3)),
+            Spot::new(Row::Court, 0),
+        );
+        field.set(
+            Some(Card::create_ancient(Season::Summer)),
+            Spot::new(Row::Court, 1),
+        );
+        let cards = count_copies(&[
+            Card::create_queen(Season::Winter, 5),
+            Card::create_count(Season::Winter),
+            Card::create_warrior(Season::Winter, 9),
+        ]);
+        // The Ancient stands in for a Queen, or for a Countess
+        assert_eq!(
+            vec![NearWin::ThreeInCourt(Rune::Queen), NearWin::CountCountess],
+            near_wins(&field, &cards, 40)
+        );
+    }
+}
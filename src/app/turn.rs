@@ -1,31 +1,59 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
 use super::{
+    card::{rune::Rune, Card},
     field::{Field, Spot},
-    Card,
+    player::Player,
+    state::GameState,
 };
 
+/// List every turn the player could take: each card in their hand, played in
+/// each spot it may go on any field, including swaps onto opponents' fields
+pub(super) fn legal_turns(state: &GameState, player_index: usize) -> Vec<Turn> {
+    let players = state.players();
+    let fields: Vec<&Field> = players.iter().map(|p| p.field()).collect();
+    let mut turns = vec![];
+    for (card_index_in_hand, card) in players[player_index].hand().iter().enumerate() {
+        let valid_spots = get_valid_spots_from_card(player_index, card, &fields);
+        for (field_index, spots) in valid_spots.into_iter().enumerate() {
+            turns.extend(spots.into_iter().map(|spot_on_field| Turn {
+                player_index,
+                field_index,
+                card_index_in_hand,
+                spot_on_field,
+            }));
+        }
+    }
+    turns
+}
+
+/// Collect the spots that the selected card may be played in, indexed by field.
+/// Swap cards may be played on any field, other cards only on the player's own field.
 pub(super) fn get_valid_spots_from_card(
     player_index: usize,
     selected_card: &Card,
-    fields: &Vec<&Field>,
+    fields: &[&Field],
 ) -> Vec<Vec<Spot>> {
     if selected_card.rune().ability().is_swap() {
-        let mut spots = vec![];
-        for i in 0..fields.len() {
-            let new_spots: Vec<Spot> = fields[i]
-                .iter()
-                .enumerate()
-                .filter_map(|(i, possible_card)| {
-                    if let Some(card) = possible_card {
-                        if selected_card.can_swap_with(&card) {
-                            return Some(Spot::from_index(i));
+        fields
+            .iter()
+            .map(|field| {
+                field
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, possible_card)| {
+                        if let Some(card) = possible_card {
+                            if selected_card.can_swap_with(&card) {
+                                return Some(Spot::from_index(i));
+                            }
                         }
-                    }
-                    None
-                })
-                .collect();
-            spots.push(new_spots);
-        }
-        spots
+                        None
+                    })
+                    .collect()
+            })
+            .collect()
     } else {
         let mut spots = vec![];
         spots.resize(fields.len(), vec![]);
@@ -41,10 +69,159 @@ pub(super) fn get_valid_spots_from_card(
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub(super) struct Turn {
     pub player_index: usize,
     pub field_index: usize,
     pub card_index_in_hand: usize,
     pub spot_on_field: Spot,
 }
-impl Turn {}
+impl Turn {
+    /// Check the turn against the rules for playing a card, without changing anything
+    pub(super) fn check_legal(&self, players: &[Player]) -> Result<(), RuleError> {
+        let player = players
+            .get(self.player_index)
+            .ok_or(RuleError::NoSuchPlayer(self.player_index))?;
+        let field = players
+            .get(self.field_index)
+            .ok_or(RuleError::NoSuchField(self.field_index))?
+            .field();
+        let card =
+            player
+                .hand()
+                .get(self.card_index_in_hand)
+                .ok_or(RuleError::NoSuchCardInHand {
+                    index: self.card_index_in_hand,
+                    hand_size: player.hand().len(),
+                })?;
+        let spot = self.spot_on_field;
+
+        if card.rune().ability().is_swap() {
+            match field.get(spot) {
+                None => Err(RuleError::NothingToSwap(spot)),
+                Some(other_card) if !card.can_swap_with(other_card) => Err(RuleError::CannotSwap {
+                    card: card.rune(),
+                    other_card: other_card.rune(),
+                }),
+                Some(_) => Ok(()),
+            }
+        } else if self.field_index != self.player_index {
+            Err(RuleError::NotOwnField(card.rune()))
+        } else if field.get(spot).is_some() {
+            Err(RuleError::SpotOccupied(spot))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The rule that an illegal turn or decision breaks
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum RuleError {
+    /// It is another player's turn
+    NotPlayersTurn { expected: usize, found: usize },
+    /// There is no player with this index at the table
+    NoSuchPlayer(usize),
+    /// There is no field with this index at the table
+    NoSuchField(usize),
+    /// The hand has no card at this index
+    NoSuchCardInHand { index: usize, hand_size: usize },
+    /// Only swap cards may be played on another player's field
+    NotOwnField(Rune),
+    /// A card without the Swap ability must be played in an open spot
+    SpotOccupied(Spot),
+    /// A card with the Swap ability must be played on another card
+    NothingToSwap(Spot),
+    /// The swap card cannot be swapped with the card in the spot
+    CannotSwap { card: Rune, other_card: Rune },
+    /// There is no prize to take from the player with this index
+    NoSuchPrize(usize),
+    /// The round is over, so no more cards may be played
+    RoundNotInProgress,
+    /// Nobody has won a round, so there is no prize to choose
+    NoPrizeToChoose,
+    /// A player may only pass when they have no legal turn
+    MustPlay,
+}
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::NotPlayersTurn { expected, found } => write!(
+                f,
+                "It is player {}'s turn, not player {}'s",
+                expected + 1,
+                found + 1
+            ),
+            RuleError::NoSuchPlayer(index) => write!(f, "There is no player {}", index + 1),
+            RuleError::NoSuchField(index) => write!(f, "There is no field {}", index + 1),
+            RuleError::NoSuchCardInHand { index, hand_size } => write!(
+                f,
+                "There is no card {} in a hand of {} cards",
+                index + 1,
+                hand_size
+            ),
+            RuleError::NotOwnField(rune) => {
+                write!(f, "A {} can only be played on your own field", rune)
+            }
+            RuleError::SpotOccupied(spot) => write!(f, "There is already a card in {}", spot),
+            RuleError::NothingToSwap(spot) => {
+                write!(f, "There is no card to swap with in {}", spot)
+            }
+            RuleError::CannotSwap { card, other_card } => {
+                write!(f, "A {} cannot be swapped with a {}", card, other_card)
+            }
+            RuleError::NoSuchPrize(index) => {
+                write!(f, "Player {} has no prize to take", index + 1)
+            }
+            RuleError::RoundNotInProgress => write!(f, "The round is over"),
+            RuleError::NoPrizeToChoose => write!(f, "There is no prize to choose"),
+            RuleError::MustPlay => write!(f, "A card can still be played, so you cannot pass"),
+        }
+    }
+}
+impl std::error::Error for RuleError {}
+
+#[cfg(test)]
+mod test {
+    use super::super::{card::Card, field::Row, rules::RuleSet, season::Season};
+    use super::*;
+
+    #[test]
+    fn test_legal_turns_include_own_spots_and_swaps_on_every_field() {
+        let mut state = GameState::new(2, RuleSet::standard());
+        let players = state.players_mut();
+        players[0].add_card_to_hand(Card::create_count(Season::Spring));
+        players[0].add_card_to_hand(Card::create_changeling(Season::Ferric));
+        players[0].play_card(
+            Card::create_archer(Season::Spring, 6),
+            Spot::new(Row::Garden, 0),
+        );
+        players[1].play_card(
+            Card::create_queen(Season::Autumn, 7),
+            Spot::new(Row::Court, 2),
+        );
+
+        let turns = legal_turns(&state, 0);
+
+        // The Count can go in any of the 9 open spots on its own field
+        let count_turns: Vec<&Turn> = turns.iter().filter(|t| t.card_index_in_hand == 0).collect();
+        assert_eq!(9, count_turns.len());
+        assert!(count_turns.iter().all(|t| t.field_index == 0));
+        assert!(!count_turns
+            .iter()
+            .any(|t| t.spot_on_field == Spot::new(Row::Garden, 0)));
+
+        // The Changeling cannot swap with the Archer, only with the Queen
+        let changeling_turns: Vec<&Turn> =
+            turns.iter().filter(|t| t.card_index_in_hand == 1).collect();
+        assert_eq!(
+            vec![&Turn {
+                player_index: 0,
+                field_index: 1,
+                card_index_in_hand: 1,
+                spot_on_field: Spot::new(Row::Court, 2),
+            }],
+            changeling_turns
+        );
+    }
+}
@@ -1,12 +1,14 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
 use super::{
-    card::{ability::Ability, rune::Rune, score::Score, Card},
+    card::{rune::Rune, Card},
     field::{Field, Row, RowOfCards, Spot},
+    score_breakdown::RowBreakdown,
     season::Season,
 };
 
-#[derive(PartialEq, Debug, Display)]
+#[derive(Clone, Copy, PartialEq, Debug, Display, Serialize, Deserialize)]
 pub(crate) enum WinCondition {
     CountCountess([Spot; 2]),
     ThreeInCourt([Spot; 3]),
@@ -15,20 +17,23 @@ pub(crate) enum WinCondition {
 }
 
 /// After a card is played in a spot on a player's field, check if that field now
-/// counts as a win condition for that player.
-pub(crate) fn check_win(field: &Field, spot: &Spot, card: &Card) -> Option<WinCondition> {
+/// counts as a win condition for that player. A field wins on points once it
+/// reaches `point_threshold`.
+pub(crate) fn check_win(
+    field: &Field,
+    spot: &Spot,
+    card: &Card,
+    point_threshold: i32,
+) -> Option<WinCondition> {
     // A Plague card can only count as a win for the TwoPlagues win condition
     if let Rune::Plague = card.rune() {
-        return if let Some(spots) = check_two_plagues(field.row(spot.row().opposite()), spot) {
-            Some(WinCondition::TwoPlagues([spots[0], spots[1]]))
-        } else {
-            None
-        };
+        return check_two_plagues(field.row(spot.row().opposite()), spot)
+            .map(|spots| WinCondition::TwoPlagues([spots[0], spots[1]]));
     }
     // After accounting for the TwoPlagues win condition, if the card was played in the
     // Garden, then only the FourtyPoints win condition is possible
     if *spot.row() == Row::Garden {
-        return if check_fourty_points(field) {
+        return if check_fourty_points(field, point_threshold) {
             Some(WinCondition::FourtyPoints)
         } else {
             None
@@ -42,7 +47,7 @@ pub(crate) fn check_win(field: &Field, spot: &Spot, card: &Card) -> Option<WinCo
             Some(WinCondition::ThreeInCourt([spots[0], spots[1], spots[2]]))
         };
     }
-    if check_fourty_points(field) {
+    if check_fourty_points(field, point_threshold) {
         Some(WinCondition::FourtyPoints)
     } else {
         None
@@ -71,7 +76,7 @@ pub(crate) fn check_two_ancients_house_rule(
 }
 /// Check the court if the rune of the played card is Ancient, Beast, Changeling, Queen,
 /// Count, or Countess. No other runes are possible for this win condition.
-fn check_court(court: &[Option<Card>; 5], rune: Rune) -> Option<Vec<Spot>> {
+fn check_court(court: &RowOfCards, rune: Rune) -> Option<Vec<Spot>> {
     let num_cards_required = match rune {
         Rune::Ancient => 0,
         Rune::Beast | Rune::Changeling | Rune::Queen => 3,
@@ -81,49 +86,28 @@ fn check_court(court: &[Option<Card>; 5], rune: Rune) -> Option<Vec<Spot>> {
     // Now, the card was played in the Court and was one of
     // Ancient, Beast, Count/Countess, Changeling, or Queen
 
-    let collected_court: Vec<&Card> = court.iter().filter_map(|o| o.as_ref()).collect();
-    if collected_court.len() < num_cards_required {
+    if court.iter().flatten().count() < num_cards_required {
         return None;
     }
     if rune == Rune::Ancient {
         // Check two ancients
-        let ancient_spots: Vec<Spot> = collected_court
-            .iter()
-            .enumerate()
-            .filter_map(|(i, &c)| {
-                if c.rune() == Rune::Ancient {
-                    Some(Spot::new(Row::Court, i as usize))
-                } else {
-                    None
-                }
-            })
-            .collect();
-        assert!(ancient_spots.len() >= 1);
+        let ancient_spots = spots_with_rune(court, Rune::Ancient);
+        assert!(!ancient_spots.is_empty());
         if ancient_spots.len() == 2 {
             return Some(ancient_spots);
         }
 
         // Check Ancient and Count or Countess
-        let count_pos = collected_court
+        let count_pos = court
             .iter()
-            .position(|c| c.rune() == Rune::Count || c.rune() == Rune::Countess);
+            .position(|c| c.is_some_and(|c| c.rune() == Rune::Count || c.rune() == Rune::Countess));
         if let Some(pos) = count_pos {
-            return Some(vec![ancient_spots[0], Spot::new(Row::Court, pos as usize)]);
+            return Some(vec![ancient_spots[0], Spot::new(Row::Court, pos)]);
         }
 
         // Check Ancient and two Beasts, Changelings, or Queens
-        for r in vec![Rune::Beast, Rune::Changeling, Rune::Queen] {
-            let mut spots: Vec<Spot> = collected_court
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &c)| {
-                    if c.rune() == r {
-                        Some(Spot::new(Row::Court, i as usize))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+        for r in [Rune::Beast, Rune::Changeling, Rune::Queen] {
+            let mut spots = spots_with_rune(court, r);
             if spots.len() == 2 {
                 spots.push(ancient_spots[0]);
                 return Some(spots);
@@ -164,7 +148,7 @@ fn check_court(court: &[Option<Card>; 5], rune: Rune) -> Option<Vec<Spot>> {
             .enumerate()
             .filter_map(|(i, &card)| {
                 if card.is_some_and(|c| c.rune() == rune || c.rune() == Rune::Ancient) {
-                    Some(Spot::new(Row::Court, i as usize))
+                    Some(Spot::new(Row::Court, i))
                 } else {
                     None
                 }
@@ -177,6 +161,21 @@ fn check_court(court: &[Option<Card>; 5], rune: Rune) -> Option<Vec<Spot>> {
     None
 }
 
+/// Collect the spots in the court holding a card of the given rune
+fn spots_with_rune(court: &RowOfCards, rune: Rune) -> Vec<Spot> {
+    court
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            if c.is_some_and(|card| card.rune() == rune) {
+                Some(Spot::new(Row::Court, i))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Check the TwoPlagues win condition, where the Plagues must be played in
 /// opposite rows (one in the Court, one in the Garden).
 fn check_two_plagues(row: &RowOfCards, spot: &Spot) -> Option<Vec<Spot>> {
@@ -185,99 +184,38 @@ fn check_two_plagues(row: &RowOfCards, spot: &Spot) -> Option<Vec<Spot>> {
         .enumerate()
         .filter_map(|(i, o)| match o {
             Some(c) => match c.rune() {
-                Rune::Plague => Some(Spot::new(spot.row().opposite(), i as usize)),
+                Rune::Plague => Some(Spot::new(spot.row().opposite(), i)),
                 _ => None,
             },
             None => None,
         })
         .collect();
 
-    if spots.len() == 0 {
+    if spots.is_empty() {
         None
     } else {
         Some(vec![*spot, spots[0]])
     }
 }
 
-/// Check if the sum of cards on the field are at least 40
-fn check_fourty_points(field: &Field) -> bool {
-    count_points_in_row(&field.court, |c| c.court_score())
-        + count_points_in_row(&field.garden, |c| c.garden_score())
-        >= 40
+/// Check if the sum of cards on the field are at least the threshold, which
+/// is 40 in the standard rules
+fn check_fourty_points(field: &Field, point_threshold: i32) -> bool {
+    count_points_in_row(&field.court, Row::Court) + count_points_in_row(&field.garden, Row::Garden)
+        >= point_threshold
 }
 
-/// Counts the total number of points in a row in the following order:
-/// 1. Count the individual points on each card
-/// 2. Count the Adj +1 modifiers from Counts/Countesses
-/// 3. Count the Row -1 modifiers from Mists
-/// 4. Count the Row xN modifiers from Weathers and Plagues
-fn count_points_in_row(row: &RowOfCards, card_score_fn: fn(&Card) -> Score) -> i32 {
-    let count_countess_locations: Vec<usize> = (0..5)
-        .filter(|i| {
-            row[*i].is_some_and(|c| match c.rune() {
-                Rune::Count | Rune::Countess => true,
-                _ => false,
-            })
-        })
-        .collect();
-    let num_mists = row
-        .iter()
-        .filter(|c| {
-            c.is_some_and(|card| match card.rune() {
-                Rune::Mist => true,
-                _ => false,
-            })
-        })
-        .count();
-    let num_weathers = row
-        .iter()
-        .filter(|c| {
-            c.is_some_and(|card| match card.rune() {
-                Rune::Weather => true,
-                _ => false,
-            })
-        })
-        .count();
-    let num_plagues = row
-        .iter()
-        .filter(|c| {
-            c.is_some_and(|card| match card.rune() {
-                Rune::Plague => true,
-                _ => false,
-            })
-        })
-        .count();
-
-    row.iter()
-        .enumerate()
-        .map(|(i, possible_card)| {
-            if let Some(card) = possible_card {
-                if let Score::Value(mut value) = card_score_fn(card) {
-                    value -= num_mists as i32;
-                    if count_countess_locations.contains(&(i + 1)) {
-                        value += 1;
-                    }
-                    if count_countess_locations.contains(&(i - 1)) {
-                        value += 1;
-                    }
-                    if card.rune().ability() != Ability::NoWeather && num_weathers > 0 {
-                        value *= num_weathers as i32;
-                    }
-                    if card.rune().ability() != Ability::AntiPlague && num_plagues > 0 {
-                        value = 0;
-                    }
-                    return value;
-                }
-            }
-            0
-        })
-        .sum()
+/// Counts the total number of points in a row, as its `RowBreakdown` works
+/// them out card by card
+fn count_points_in_row(row: &RowOfCards, row_kind: Row) -> i32 {
+    RowBreakdown::of_row(row, row_kind).total()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[allow(clippy::needless_range_loop)]
     fn setup_field(garden_cards: [Option<Card>; 5], court_cards: [Option<Card>; 5]) -> Field {
         let mut field = Field::new();
         for i in 0..5usize {
@@ -294,6 +232,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_two_plagues_meets_win_condition() {
         let field = setup_field(
             [
@@ -312,7 +251,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Garden, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some(), "Should be a win condition");
         assert!(
@@ -337,7 +276,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
         assert!(win_condition.is_none());
     }
 
@@ -354,11 +293,12 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
         assert!(win_condition.is_none());
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_count_and_countess_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -371,7 +311,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -381,6 +321,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_countess_and_ancient_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -393,7 +334,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -403,6 +344,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_count_and_ancient_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -415,7 +357,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -442,12 +384,13 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_none());
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_two_ancients_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -460,7 +403,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -470,6 +413,58 @@ mod test {
     }
 
     #[test]
+    fn test_ancient_and_two_queens_with_gaps_gives_spots_of_all_three_cards() {
+        // The spots were once counted along the court with its gaps left out,
+        // which put them in the wrong places
+        let field = setup_field(
+            [None; 5],
+            [
+                Some(Card::create_queen(Season::Spring, 3)),
+                None,
+                Some(Card::create_ancient(Season::Summer)),
+                None,
+                Some(Card::create_queen(Season::Winter, 5)),
+            ],
+        );
+        let spot = Spot::new(Row::Court, 2);
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
+
+        assert_eq!(
+            Some(WinCondition::ThreeInCourt([
+                Spot::new(Row::Court, 0),
+                Spot::new(Row::Court, 4),
+                Spot::new(Row::Court, 2)
+            ])),
+            win_condition
+        );
+    }
+
+    #[test]
+    fn test_ancient_and_count_with_gaps_gives_spots_of_both_cards() {
+        let field = setup_field(
+            [None; 5],
+            [
+                None,
+                Some(Card::create_count(Season::Autumn)),
+                None,
+                Some(Card::create_ancient(Season::Ferric)),
+                None,
+            ],
+        );
+        let spot = Spot::new(Row::Court, 3);
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
+
+        assert_eq!(
+            Some(WinCondition::CountCountess([
+                Spot::new(Row::Court, 3),
+                Spot::new(Row::Court, 1)
+            ])),
+            win_condition
+        );
+    }
+
+    #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_three_queens_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -482,7 +477,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -492,6 +487,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_three_beasts_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -504,7 +500,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -514,6 +510,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_three_changelings_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -526,7 +523,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -536,6 +533,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_one_ancient_and_two_queens_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -548,7 +546,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -558,6 +556,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_one_ancient_and_two_beasts_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -570,7 +569,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -580,6 +579,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_one_ancient_and_two_changelings_in_court_gives_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -592,7 +592,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -602,6 +602,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_two_ancients_and_one_queen_in_court_gives_count_countess_win_condition() {
         let field = setup_field(
             [None; 5],
@@ -614,7 +615,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 3);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -624,6 +625,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn test_fourty_points_only_mundane() {
         let field = setup_field(
             [
@@ -636,7 +638,7 @@ mod test {
             [None; 5],
         );
         let spot = Spot::new(Row::Garden, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_some());
         assert!(match win_condition.unwrap() {
@@ -658,7 +660,7 @@ mod test {
             ],
         );
         let spot = Spot::new(Row::Court, 0);
-        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap());
+        let win_condition = check_win(&field, &spot, field.get(spot).as_ref().unwrap(), 40);
 
         assert!(win_condition.is_none());
 
@@ -670,7 +672,7 @@ mod test {
                 Some(Card::create_archer(Season::Ferric, 8)),
                 None,
             ],
-            |c| c.court_score(),
+            Row::Court,
         );
         assert_eq!(14, points);
     }
@@ -1,6 +1,13 @@
+use std::path::Path;
+
 mod app;
 
 fn main() {
     let mut game = app::App::new();
-    game.run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [command, path] if command == "replay" => game.view_replay(Path::new(path)),
+        [command, options @ ..] if command == "simulate" => game.simulate(options),
+        _ => game.run(),
+    }
 }